@@ -7,10 +7,21 @@ export class CdkStack extends cdk.Stack {
   constructor(scope: Construct, id: string, props?: cdk.StackProps) {
     super(scope, id, props);
 
+    // an empty allow-list blocks every browser, so fail at synth instead of deploying it
+    const corsAllowedOrigins = process.env.CORS_ALLOWED_ORIGINS?.trim();
+    if (!corsAllowedOrigins) {
+      throw new Error("CORS_ALLOWED_ORIGINS must be given as comma-separated origins");
+    }
+
     const jiraIssueHandlerLambda = new lambda.DockerImageFunction(this, "jiraIssueLoader", {
       functionName: "jira-issue-loader",
       code: lambda.DockerImageCode.fromImageAsset("../"),
       timeout: cdk.Duration.minutes(1),
+      environment: {
+        CORS_ALLOWED_ORIGINS: corsAllowedOrigins,
+        // comma-separated https base URLs of Jira Data Center/Server. Empty allows none
+        JIRA_SERVER_BASE_URLS: process.env.JIRA_SERVER_BASE_URLS ?? "",
+      },
     });
 
     const restApi = new apigw.LambdaRestApi(this, "Endpoint", {
@@ -1,29 +1,196 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
+use crate::{
+    issue::{EstimateKind, JiraIssue},
+    issue_graph::{CriticalPath, IssueCycle, IssueLayers},
+    jira_issue_request::LoadingReport,
+    jira_oauth::{JiraCloudResource, OAuthToken},
+    jira_sprint_request::{JiraBoard, JiraSprint, SprintState},
+};
+
+/// scope of issues to load. Issues matched with all given conditions are loaded
 #[derive(Deserialize, Default)]
 pub struct IssueSearchCondition {
+    /// id or name of sprint
     pub sprint: Option<String>,
+    /// key of epic
     pub epic: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 pub struct IssueLoadingRequest {
+    #[serde(default)]
     pub issues: Vec<String>,
+    /// id of saved filter. Issues matched with JQL of the filter are loaded with `issues`
+    #[serde(default)]
+    pub filter: Option<u64>,
+    /// depth to follow links from requested issues. 0 loads requested issues only
+    #[serde(default)]
+    pub depth: u32,
+    /// max number of issues to stop following links
+    #[serde(default)]
+    pub max_issues: Option<usize>,
+    /// names of link types to treat as dependency. Empty means `Blocks` only
+    #[serde(default)]
+    pub link_types: Vec<String>,
+    /// load parents of loaded issues up to the top of hierarchy, such as epics and initiatives
+    #[serde(default)]
+    pub load_parents: bool,
+}
+
+#[derive(Deserialize, Default)]
+pub struct IssueConditionLoadingRequest {
+    #[serde(flatten)]
+    pub condition: IssueSearchCondition,
+    #[serde(flatten)]
+    pub request: IssueLoadingRequest,
+}
+
+#[derive(Serialize)]
+pub struct IssueLoadingResponse {
+    pub issues: Vec<JiraIssue>,
+    pub cycles: Vec<IssueCycle>,
+    #[serde(flatten)]
+    pub report: LoadingReport,
+}
+
+#[derive(Serialize)]
+pub struct IssueLayersResponse {
+    #[serde(flatten)]
+    pub layers: IssueLayers,
+    pub cycles: Vec<IssueCycle>,
+    #[serde(flatten)]
+    pub report: LoadingReport,
 }
 
 #[derive(Deserialize)]
+pub struct CriticalPathRequest {
+    #[serde(flatten)]
+    pub request: IssueLoadingRequest,
+    #[serde(default)]
+    pub estimate: EstimateKind,
+}
+
+#[derive(Serialize)]
+pub struct CriticalPathResponse {
+    pub issues: Vec<JiraIssue>,
+    #[serde(flatten)]
+    pub critical_path: CriticalPath,
+    pub cycles: Vec<IssueCycle>,
+    #[serde(flatten)]
+    pub report: LoadingReport,
+}
+
+#[derive(Deserialize, Default)]
 pub struct IssueSearchRequest {
     pub jql: String,
+    /// page number from 1. Ignored if `cursor` is given
+    #[serde(default)]
+    pub page: u32,
+    /// number of issues in a page
+    #[serde(default)]
+    pub page_size: Option<u32>,
+    /// cursor of the page given by the previous response
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueSearchResponse {
+    pub issues: Vec<JiraIssue>,
+    /// number of all issues matched. It is approximate with `search/jql` API
+    pub total: Option<usize>,
     pub page: u32,
+    pub page_size: u32,
+    pub has_next: bool,
+    /// cursor of the next page. None if this page is the last one
+    pub cursor: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct BoardListRequest {
+    /// filter boards whose name contains it
+    #[serde(default)]
+    pub name: Option<String>,
+    /// key or id of project that boards belong to
+    #[serde(default)]
+    pub project: Option<String>,
+    /// page number from 1
+    #[serde(default)]
+    pub page: u32,
+    /// number of boards in a page
+    #[serde(default)]
+    pub page_size: Option<u32>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardListResponse {
+    pub boards: Vec<JiraBoard>,
+    pub page: u32,
+    pub page_size: u32,
+    pub has_next: bool,
+}
+
+#[derive(Deserialize, Default)]
+pub struct SprintListRequest {
+    pub board_id: u64,
+    /// states of sprints to list. Empty means all states
+    #[serde(default)]
+    pub states: Vec<SprintState>,
+    /// page number from 1
+    #[serde(default)]
+    pub page: u32,
+    /// number of sprints in a page
+    #[serde(default)]
+    pub page_size: Option<u32>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintListResponse {
+    pub sprints: Vec<JiraSprint>,
+    pub page: u32,
+    pub page_size: u32,
+    pub has_next: bool,
+}
+
+/// reference to a link type by its id or name
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueLinkTypeRef {
+    Id(String),
+    Name(String),
+}
+
+impl Default for IssueLinkTypeRef {
+    fn default() -> Self {
+        IssueLinkTypeRef::Name(String::from("Blocks"))
+    }
 }
 
 #[derive(Deserialize)]
 pub struct CreateLinkRequest {
     pub inward_issue: String,
     pub outward_issue: String,
+    #[serde(default)]
+    pub link_type: IssueLinkTypeRef,
 }
 
 #[derive(Deserialize)]
 pub struct DeleteLinkRequest {
     pub id: String,
 }
+
+#[derive(Deserialize)]
+pub struct OAuthCallbackRequest {
+    pub code: String,
+}
+
+#[derive(Serialize)]
+pub struct OAuthTokenResponse {
+    #[serde(flatten)]
+    pub token: OAuthToken,
+    pub resources: Vec<JiraCloudResource>,
+}
@@ -0,0 +1,71 @@
+extern crate jira_issue_loader;
+
+use std::{convert::Infallible, env, net::SocketAddr};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Server,
+};
+use lambda_http::{Body, Error};
+
+/// convert request of hyper to the one of lambda
+async fn to_lambda_request(
+    request: hyper::Request<hyper::Body>,
+) -> Result<lambda_http::Request, Error> {
+    let (parts, body) = request.into_parts();
+    let bytes = hyper::body::to_bytes(body).await?;
+
+    let body = if bytes.is_empty() {
+        Body::Empty
+    } else {
+        match String::from_utf8(bytes.to_vec()) {
+            Ok(text) => Body::Text(text),
+            Err(_) => Body::Binary(bytes.to_vec()),
+        }
+    };
+
+    Ok(lambda_http::Request::from_parts(parts, body))
+}
+
+/// convert response of lambda to the one of hyper
+fn to_hyper_response(response: lambda_http::Response<Body>) -> hyper::Response<hyper::Body> {
+    let (parts, body) = response.into_parts();
+
+    let body = match body {
+        Body::Empty => hyper::Body::empty(),
+        Body::Text(text) => hyper::Body::from(text),
+        Body::Binary(bytes) => hyper::Body::from(bytes),
+    };
+
+    hyper::Response::from_parts(parts, body)
+}
+
+async fn serve(
+    request: hyper::Request<hyper::Body>,
+) -> Result<hyper::Response<hyper::Body>, Error> {
+    let request = to_lambda_request(request).await?;
+    let response = jira_issue_loader::handler(request).await?;
+
+    Ok(to_hyper_response(response))
+}
+
+/// run the same routes as lambda on plain HTTP server.
+/// Listening address can be configured with `HOST` and `PORT` environment variables.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_target(false)
+        .init();
+
+    let host = env::var("HOST").unwrap_or(String::from("0.0.0.0"));
+    let port = env::var("PORT").unwrap_or(String::from("3000"));
+    let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
+
+    let make_service = make_service_fn(|_conn| async { Ok::<_, Infallible>(service_fn(serve)) });
+
+    tracing::info!("listening on http://{}", addr);
+    Server::bind(&addr).serve(make_service).await?;
+
+    Ok(())
+}
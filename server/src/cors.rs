@@ -0,0 +1,203 @@
+use std::{env, sync::OnceLock};
+
+use lambda_http::{http::HeaderValue, Body, Request, Response};
+
+use crate::error::ApiError;
+
+/// origins allowed when `CORS_ALLOWED_ORIGINS` is not given. 8080 for wiremock
+const DEFAULT_ORIGINS: [&str; 2] = ["http://localhost:5173", "http://localhost:8080"];
+const DEFAULT_HEADERS: [&str; 8] = [
+    "content-type",
+    "x-api-key",
+    "x-user-domain",
+    "x-user-email",
+    "x-user-token",
+    "x-user-base-url",
+    "x-user-cloud-id",
+    "x-user-search-api",
+];
+const ALLOWED_METHODS: &str = "GET,POST,OPTIONS";
+
+/// Configuration of CORS.
+///
+/// Origin can contain a wildcard as subdomain such as `https://*.example.com`, or be `*` to allow
+/// any origin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub max_age: Option<u64>,
+}
+
+static CONFIG: OnceLock<CorsConfig> = OnceLock::new();
+
+/// split comma-separated list
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string())
+        .collect()
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            allowed_origins: DEFAULT_ORIGINS.iter().map(|v| v.to_string()).collect(),
+            allowed_headers: DEFAULT_HEADERS.iter().map(|v| v.to_string()).collect(),
+            max_age: None,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// load configuration from `CORS_ALLOWED_ORIGINS`, `CORS_ALLOWED_HEADERS` and `CORS_MAX_AGE`
+    /// environment variables. Lists are comma-separated
+    pub fn from_env() -> CorsConfig {
+        let default = CorsConfig::default();
+
+        CorsConfig {
+            allowed_origins: env::var("CORS_ALLOWED_ORIGINS")
+                .map(|v| split_list(&v))
+                .unwrap_or(default.allowed_origins),
+            allowed_headers: env::var("CORS_ALLOWED_HEADERS")
+                .map(|v| split_list(&v))
+                .unwrap_or(default.allowed_headers),
+            max_age: env::var("CORS_MAX_AGE")
+                .ok()
+                .and_then(|v| v.trim().parse().ok()),
+        }
+    }
+
+    /// get configuration loaded at the first call
+    pub fn global() -> &'static CorsConfig {
+        CONFIG.get_or_init(CorsConfig::from_env)
+    }
+
+    /// get the value of `Access-Control-Allow-Origin` for the origin. Return None if the origin is
+    /// not allowed
+    pub fn allowed_origin(&self, origin: &str) -> Option<String> {
+        self.allowed_origins.iter().find_map(|allowed| {
+            if allowed == "*" {
+                return Some(String::from("*"));
+            }
+
+            let matched = match allowed.split_once("*.") {
+                Some((scheme, domain)) => origin
+                    .strip_prefix(scheme)
+                    .and_then(|v| v.strip_suffix(domain))
+                    .and_then(|v| v.strip_suffix('.'))
+                    .map(|v| !v.is_empty() && !v.contains('/'))
+                    .unwrap_or(false),
+                None => allowed == origin,
+            };
+
+            matched.then(|| origin.to_string())
+        })
+    }
+
+    /// add CORS headers to the response for the request
+    pub fn apply(&self, event: &Request, mut response: Response<Body>) -> Response<Body> {
+        let origin = event
+            .headers()
+            .get("origin")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| self.allowed_origin(v))
+            .and_then(|v| HeaderValue::from_str(&v).ok());
+
+        let headers = response.headers_mut();
+        headers.insert("vary", HeaderValue::from_static("origin"));
+
+        if let Some(origin) = origin {
+            // credentials such as the cookie of refresh token can not be used with any origin
+            if origin != "*" {
+                headers.insert(
+                    "access-control-allow-credentials",
+                    HeaderValue::from_static("true"),
+                );
+            }
+            headers.insert("access-control-allow-origin", origin);
+            headers.insert(
+                "access-control-allow-methods",
+                HeaderValue::from_static(ALLOWED_METHODS),
+            );
+        }
+
+        response
+    }
+
+    /// make response for preflight request
+    pub fn preflight(&self) -> Result<Response<Body>, ApiError> {
+        let mut builder = Response::builder().status(200).header(
+            "access-control-allow-headers",
+            self.allowed_headers.join(","),
+        );
+
+        if let Some(max_age) = self.max_age {
+            builder = builder.header("access-control-max-age", max_age);
+        }
+
+        Ok(builder
+            .body(Body::Text(String::from("{}")))
+            .expect("should be valid response"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CorsConfig;
+
+    fn config(origins: &[&str]) -> CorsConfig {
+        CorsConfig {
+            allowed_origins: origins.iter().map(|v| v.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn allow_exact_origin() {
+        // arrange
+        let config = config(&["https://example.com"]);
+
+        // do
+        let allowed = config.allowed_origin("https://example.com");
+        let denied = config.allowed_origin("https://other.example.com");
+
+        // verify
+        assert_eq!(allowed, Some(String::from("https://example.com")));
+        assert_eq!(denied, None);
+    }
+
+    #[test]
+    fn allow_wildcard_subdomain() {
+        // arrange
+        let config = config(&["https://*.example.com"]);
+
+        // do
+        let subdomain = config.allowed_origin("https://app.example.com");
+        let nested = config.allowed_origin("https://a.b.example.com");
+        let apex = config.allowed_origin("https://example.com");
+        let other_scheme = config.allowed_origin("http://app.example.com");
+        let other_domain = config.allowed_origin("https://app.badexample.com");
+
+        // verify
+        assert_eq!(subdomain, Some(String::from("https://app.example.com")));
+        assert_eq!(nested, Some(String::from("https://a.b.example.com")));
+        assert_eq!(apex, None);
+        assert_eq!(other_scheme, None);
+        assert_eq!(other_domain, None);
+    }
+
+    #[test]
+    fn allow_any_origin() {
+        // arrange
+        let config = config(&["*"]);
+
+        // do
+        let allowed = config.allowed_origin("https://example.com");
+
+        // verify
+        assert_eq!(allowed, Some(String::from("*")));
+    }
+}
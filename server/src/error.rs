@@ -1,18 +1,301 @@
-use isahc::{http::StatusCode, Response};
-use lambda_http::Body;
-
-/// make empty bad request
-pub fn bad_request() -> Response<Body> {
-    Response::builder()
-        .status(StatusCode::BAD_REQUEST)
-        .body(Body::Empty)
-        .unwrap()
-}
-
-/// make empty internal server error
-pub fn internal_server_error() -> Response<Body> {
-    Response::builder()
-        .status(StatusCode::INTERNAL_SERVER_ERROR)
-        .body(Body::Empty)
-        .unwrap()
+use std::{
+    fmt::Display,
+    time::{Duration, SystemTime},
+};
+
+use isahc::{http::StatusCode, AsyncReadResponseExt};
+use lambda_http::{Body, Response};
+use serde::{ser::SerializeStruct, Serialize};
+use serde_json::Value;
+
+/// Errors of API. Each error is mapped to a JSON response with machine-readable code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    /// request from client is invalid
+    InvalidRequest(String),
+    /// required header is not given
+    MissingHeader(String),
+    /// the route does not accept the method. Contains allowed methods
+    MethodNotAllowed(Vec<String>),
+    /// no route matches the path of the request
+    RouteNotFound(String),
+    /// Jira rejected credential
+    Unauthorized,
+    /// credential does not have permission
+    Forbidden,
+    /// resource is not found in Jira
+    NotFound,
+    /// Jira limited rate of requests
+    RateLimited { retry_after: Option<u64> },
+    /// Jira returned 5xx status
+    JiraUnavailable(u16),
+    /// response from Jira can not be parsed
+    InvalidResponse(String),
+    /// failed to communicate with Jira
+    Network(String),
+    /// server is not configured for the request
+    NotConfigured(String),
+}
+
+impl ApiError {
+    /// machine-readable code of the error
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::InvalidRequest(_) => "invalid_request",
+            ApiError::MissingHeader(_) => "missing_header",
+            ApiError::MethodNotAllowed(_) => "method_not_allowed",
+            ApiError::RouteNotFound(_) => "route_not_found",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::Forbidden => "forbidden",
+            ApiError::NotFound => "not_found",
+            ApiError::RateLimited { .. } => "rate_limited",
+            ApiError::JiraUnavailable(_) => "jira_unavailable",
+            ApiError::InvalidResponse(_) => "invalid_response",
+            ApiError::Network(_) => "network_error",
+            ApiError::NotConfigured(_) => "not_configured",
+        }
+    }
+
+    /// status code of response for the error
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::InvalidRequest(_) | ApiError::MissingHeader(_) => StatusCode::BAD_REQUEST,
+            ApiError::MethodNotAllowed(_) => StatusCode::METHOD_NOT_ALLOWED,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden => StatusCode::FORBIDDEN,
+            ApiError::NotFound | ApiError::RouteNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::JiraUnavailable(_) | ApiError::InvalidResponse(_) | ApiError::Network(_) => {
+                StatusCode::BAD_GATEWAY
+            }
+            ApiError::NotConfigured(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// get error from status and body of Jira response. Return None if the status is successful
+    pub fn from_jira_status(status: StatusCode, body: &str) -> Option<ApiError> {
+        if status.is_success() {
+            return None;
+        }
+
+        let error = match status {
+            StatusCode::UNAUTHORIZED => ApiError::Unauthorized,
+            StatusCode::FORBIDDEN => ApiError::Forbidden,
+            StatusCode::NOT_FOUND => ApiError::NotFound,
+            StatusCode::TOO_MANY_REQUESTS => ApiError::RateLimited { retry_after: None },
+            v if v.is_server_error() => ApiError::JiraUnavailable(v.as_u16()),
+            _ => ApiError::InvalidRequest(jira_error_messages(body)),
+        };
+
+        Some(error)
+    }
+
+    /// get error from response of Jira. Return None if the response is successful
+    pub async fn from_jira_response(
+        res: &mut isahc::Response<isahc::AsyncBody>,
+    ) -> Option<ApiError> {
+        if res.status().is_success() {
+            return None;
+        }
+
+        let retry_after = res
+            .headers()
+            .get("retry-after")
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        let body = res.text().await.unwrap_or_default();
+
+        match ApiError::from_jira_status(res.status(), &body) {
+            Some(ApiError::RateLimited { .. }) => Some(ApiError::RateLimited { retry_after }),
+            v => v,
+        }
+    }
+
+    /// make JSON response for the error
+    pub fn to_response(&self) -> Response<Body> {
+        let mut builder = Response::builder()
+            .status(self.status())
+            .header("content-type", "application/json");
+
+        if let ApiError::MethodNotAllowed(methods) = self {
+            builder = builder.header("allow", methods.join(","));
+        }
+
+        builder
+            .body(Body::Text(
+                serde_json::to_string(self).expect("should be valid JSON"),
+            ))
+            .expect("should be valid response")
+    }
+}
+
+/// parse `Retry-After` header as seconds to wait. The header is seconds or HTTP-date
+fn parse_retry_after(value: &str) -> Option<u64> {
+    let value = value.trim();
+
+    value.parse::<u64>().ok().or_else(|| {
+        let date = httpdate::parse_http_date(value).ok()?;
+        // the date in past means that the request can be retried now
+        let wait = date
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO);
+
+        Some(wait.as_secs() + u64::from(wait.subsec_nanos() > 0))
+    })
+}
+
+/// get messages from error response of Jira
+fn jira_error_messages(body: &str) -> String {
+    let json: Value = serde_json::from_str(body).unwrap_or_default();
+    let messages = json["errorMessages"]
+        .as_array()
+        .map(|v| v.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let errors = json["errors"]
+        .as_object()
+        .map(|v| v.values().filter_map(|v| v.as_str()).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    [messages, errors].concat().join(", ")
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::InvalidRequest(message) => write!(f, "Invalid request: {}", message),
+            ApiError::MissingHeader(name) => write!(f, "Missing header: {}", name),
+            ApiError::MethodNotAllowed(methods) => {
+                write!(f, "Method not allowed. Allowed: {}", methods.join(","))
+            }
+            ApiError::RouteNotFound(path) => write!(f, "Route not found: {}", path),
+            ApiError::Unauthorized => write!(f, "Jira rejected the credential"),
+            ApiError::Forbidden => write!(f, "No permission to access the resource in Jira"),
+            ApiError::NotFound => write!(f, "The resource is not found in Jira"),
+            ApiError::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(
+                f,
+                "Rate limited by Jira, retry after {} seconds",
+                retry_after
+            ),
+            ApiError::RateLimited { retry_after: None } => write!(f, "Rate limited by Jira"),
+            ApiError::JiraUnavailable(status) => write!(f, "Jira returned status {}", status),
+            ApiError::InvalidResponse(message) => {
+                write!(f, "Invalid response from Jira: {}", message)
+            }
+            ApiError::Network(message) => write!(f, "Failed to request to Jira: {}", message),
+            ApiError::NotConfigured(message) => write!(f, "Not configured: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// serialize as the body of error response
+impl Serialize for ApiError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ApiError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<isahc::Error> for ApiError {
+    fn from(value: isahc::Error) -> Self {
+        ApiError::Network(value.to_string())
+    }
+}
+
+impl From<isahc::http::Error> for ApiError {
+    fn from(value: isahc::http::Error) -> Self {
+        ApiError::InvalidRequest(value.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(value: serde_json::Error) -> Self {
+        ApiError::InvalidResponse(value.to_string())
+    }
+}
+
+impl From<std::io::Error> for ApiError {
+    fn from(value: std::io::Error) -> Self {
+        ApiError::Network(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use isahc::http::StatusCode;
+
+    use std::time::{Duration, SystemTime};
+
+    use super::{parse_retry_after, ApiError};
+
+    #[test]
+    fn map_jira_status_to_error() {
+        // do
+        let ok = ApiError::from_jira_status(StatusCode::OK, "");
+        let unauthorized = ApiError::from_jira_status(StatusCode::UNAUTHORIZED, "");
+        let unavailable = ApiError::from_jira_status(StatusCode::SERVICE_UNAVAILABLE, "");
+
+        // verify
+        assert_eq!(ok, None);
+        assert_eq!(unauthorized, Some(ApiError::Unauthorized));
+        assert_eq!(unavailable, Some(ApiError::JiraUnavailable(503)));
+    }
+
+    #[test]
+    fn get_messages_from_jira_error() {
+        // arrange
+        let body = r#"{"errorMessages": ["Error in the JQL Query"], "errors": {}}"#;
+
+        // do
+        let error = ApiError::from_jira_status(StatusCode::BAD_REQUEST, body);
+
+        // verify
+        assert_eq!(
+            error,
+            Some(ApiError::InvalidRequest(
+                "Error in the JQL Query".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn make_json_response() {
+        // arrange
+        let error = ApiError::MissingHeader("x-user-token".to_string());
+
+        // do
+        let response = error.to_response();
+
+        // verify
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.body(),
+            &lambda_http::Body::Text(
+                r#"{"code":"missing_header","message":"Missing header: x-user-token"}"#.to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_retry_after_in_seconds_and_date() {
+        // arrange
+        let future = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(30));
+
+        // do
+        let seconds = parse_retry_after("120");
+        let date = parse_retry_after(&future);
+        let past = parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT");
+        let invalid = parse_retry_after("soon");
+
+        // verify
+        assert_eq!(seconds, Some(120));
+        assert!(date.is_some_and(|v| (29..=30).contains(&v)));
+        assert_eq!(past, Some(0));
+        assert_eq!(invalid, None);
+    }
 }
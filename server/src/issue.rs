@@ -3,12 +3,52 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
+use crate::error::ApiError;
+
+/// default field id of story points. Team-managed projects of Jira Cloud use this field for
+/// "Story point estimate", but it differs in company-managed projects and Jira Data Center
+pub const DEFAULT_STORY_POINTS_FIELD: &str = "customfield_10016";
+
+/// fields to request for issues except story points
+pub const ISSUE_FIELDS: [&str; 13] = [
+    "status",
+    "issuetype",
+    "issuelinks",
+    "subtasks",
+    "summary",
+    "timeoriginalestimate",
+    "assignee",
+    "reporter",
+    "priority",
+    "labels",
+    "components",
+    "fixVersions",
+    "parent",
+];
+
+/// fields to request for issues with the field of story points
+pub fn issue_fields(story_points_field: &str) -> Vec<&str> {
+    let mut fields = ISSUE_FIELDS.to_vec();
+    fields.push(story_points_field);
+    fields
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraIssueLinkType {
+    pub id: String,
+    pub name: String,
+    pub inward: String,
+    pub outward: String,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct JiraIssueLink {
     pub id: String,
     pub outward_issue: String,
     pub inward_issue: String,
+    pub link_type: Option<JiraIssueLinkType>,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
@@ -27,7 +67,67 @@ pub struct JiraIssueType {
     pub avatar_url: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+/// parent of issue, such as epic of story or story of subtask
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraIssueParent {
+    pub key: String,
+    pub issue_type: Option<JiraIssueType>,
+    pub summary: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraUser {
+    /// identifier of the user. Account id in Jira Cloud, and user name in Jira Data Center/Server
+    pub id: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraPriority {
+    pub id: String,
+    pub name: String,
+    pub icon_url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraComponent {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraVersion {
+    pub id: String,
+    pub name: String,
+    pub released: bool,
+    pub release_date: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraIssueEstimate {
+    pub story_points: Option<f64>,
+    /// original estimate in seconds
+    pub original_estimate: Option<u64>,
+}
+
+/// kind of estimate to use as weight of issue
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum EstimateKind {
+    #[default]
+    StoryPoints,
+    /// original estimate in hours
+    OriginalEstimate,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct JiraIssue {
     pub key: String,
@@ -38,6 +138,14 @@ pub struct JiraIssue {
     pub self_url: Option<String>,
     pub links: Vec<JiraIssueLink>,
     pub subtasks: Vec<String>,
+    pub estimate: Option<JiraIssueEstimate>,
+    pub assignee: Option<JiraUser>,
+    pub reporter: Option<JiraUser>,
+    pub priority: Option<JiraPriority>,
+    pub labels: Vec<String>,
+    pub components: Vec<JiraComponent>,
+    pub fix_versions: Vec<JiraVersion>,
+    pub parent: Option<JiraIssueParent>,
 }
 
 impl JiraIssue {
@@ -58,6 +166,29 @@ impl JiraIssue {
             ..self.clone()
         }
     }
+
+    /// get estimate of the issue as weight. Issue without estimate is treated as 0
+    pub fn estimate_of(&self, kind: EstimateKind) -> f64 {
+        let estimate = self.estimate.as_ref();
+
+        match kind {
+            EstimateKind::StoryPoints => estimate.and_then(|v| v.story_points),
+            EstimateKind::OriginalEstimate => estimate
+                .and_then(|v| v.original_estimate)
+                .map(|v| v as f64 / 3600.0),
+        }
+        .unwrap_or_default()
+    }
+}
+
+/// json to issue link type
+pub fn as_issue_link_type(v: &Map<String, Value>) -> JiraIssueLinkType {
+    JiraIssueLinkType {
+        id: v["id"].as_str().map(|v| v.into()).unwrap_or_default(),
+        name: v["name"].as_str().map(|v| v.into()).unwrap_or_default(),
+        inward: v["inward"].as_str().map(|v| v.into()).unwrap_or_default(),
+        outward: v["outward"].as_str().map(|v| v.into()).unwrap_or_default(),
+    }
 }
 
 /// json to issue link
@@ -68,18 +199,21 @@ fn as_issuelink(value: &[Value], issue_key: &str) -> Vec<JiraIssueLink> {
             let id = v["id"].as_str();
             let outward = v["outwardIssue"]["key"].as_str();
             let inward = v["inwardIssue"]["key"].as_str();
+            let link_type = v["type"].as_object().map(as_issue_link_type);
 
             match (id, outward, inward) {
                 (Some(id), Some(outward_issue), None) => Some(JiraIssueLink {
                     id: id.to_string(),
                     outward_issue: outward_issue.to_string(),
                     inward_issue: issue_key.to_string(),
+                    link_type,
                 }),
                 (Some(id), None, Some(inward_issue)) => Some(JiraIssueLink {
                     id: id.to_string(),
 
                     outward_issue: issue_key.to_string(),
                     inward_issue: inward_issue.to_string(),
+                    link_type,
                 }),
                 (_, _, _) => None,
             }
@@ -91,60 +225,134 @@ fn as_issuelink(value: &[Value], issue_key: &str) -> Vec<JiraIssueLink> {
 fn as_subtasks(value: &[Value]) -> Vec<String> {
     value
         .iter()
-        .map(|v| v["key"].as_str().expect("key must not null").to_string())
+        .filter_map(|v| v["key"].as_str().map(|v| v.to_string()))
         .collect()
 }
 
 fn as_status(v: &Map<String, Value>) -> JiraStatus {
     JiraStatus {
-        id: v["id"]
-            .as_str()
-            .map(|v| v.into())
-            .unwrap_or(String::default()),
+        id: v["id"].as_str().map(|v| v.into()).unwrap_or_default(),
         name: v
             .get("name")
             .and_then(|v| v.as_str())
             .map(|v| v.into())
-            .unwrap_or(String::default()),
+            .unwrap_or_default(),
         status_category: v
             .get("statusCategory")
             .and_then(|v| v.as_object())
-            .map(|v| {
-                v["name"]
-                    .as_str()
-                    .map(|v| v.into())
-                    .unwrap_or(String::default())
-            })
-            .unwrap_or(String::default()),
+            .map(|v| v["name"].as_str().map(|v| v.into()).unwrap_or_default())
+            .unwrap_or_default(),
     }
 }
 
 fn as_issue_type(v: &Map<String, Value>) -> JiraIssueType {
     JiraIssueType {
-        id: v["id"]
-            .as_str()
+        id: v["id"].as_str().map(|v| v.into()).unwrap_or_default(),
+        name: v["name"].as_str().map(|v| v.into()).unwrap_or_default(),
+        avatar_url: v.get("iconUrl").and_then(|v| v.as_str()).map(|v| v.into()),
+    }
+}
+
+fn as_estimate(fields: &Value, story_points_field: &str) -> Option<JiraIssueEstimate> {
+    let story_points = fields[story_points_field].as_f64();
+    let original_estimate = fields["timeoriginalestimate"].as_u64();
+
+    match (story_points, original_estimate) {
+        (None, None) => None,
+        _ => Some(JiraIssueEstimate {
+            story_points,
+            original_estimate,
+        }),
+    }
+}
+
+fn as_parent(v: &Map<String, Value>) -> Option<JiraIssueParent> {
+    let fields = v.get("fields");
+
+    Some(JiraIssueParent {
+        key: v.get("key").and_then(|v| v.as_str())?.into(),
+        issue_type: fields
+            .and_then(|v| v["issuetype"].as_object())
+            .map(as_issue_type),
+        summary: fields.and_then(|v| v["summary"].as_str()).map(|v| v.into()),
+    })
+}
+
+fn as_user(v: &Map<String, Value>) -> JiraUser {
+    JiraUser {
+        id: v
+            .get("accountId")
+            .or(v.get("name"))
+            .and_then(|v| v.as_str())
             .map(|v| v.into())
-            .unwrap_or(String::default()),
-        name: v["name"]
+            .unwrap_or_default(),
+        display_name: v["displayName"]
             .as_str()
             .map(|v| v.into())
-            .unwrap_or(String::default()),
-        avatar_url: v["iconUrl"].as_str().map(|v| v.into()),
+            .unwrap_or_default(),
+        avatar_url: v
+            .get("avatarUrls")
+            .and_then(|v| v["48x48"].as_str())
+            .map(|v| v.into()),
+    }
+}
+
+fn as_priority(v: &Map<String, Value>) -> JiraPriority {
+    JiraPriority {
+        id: v["id"].as_str().map(|v| v.into()).unwrap_or_default(),
+        name: v["name"].as_str().map(|v| v.into()).unwrap_or_default(),
+        icon_url: v.get("iconUrl").and_then(|v| v.as_str()).map(|v| v.into()),
+    }
+}
+
+fn as_component(v: &Map<String, Value>) -> JiraComponent {
+    JiraComponent {
+        id: v["id"].as_str().map(|v| v.into()).unwrap_or_default(),
+        name: v["name"].as_str().map(|v| v.into()).unwrap_or_default(),
+    }
+}
+
+fn as_version(v: &Map<String, Value>) -> JiraVersion {
+    JiraVersion {
+        id: v["id"].as_str().map(|v| v.into()).unwrap_or_default(),
+        name: v["name"].as_str().map(|v| v.into()).unwrap_or_default(),
+        released: v
+            .get("released")
+            .and_then(|v| v.as_bool())
+            .unwrap_or_default(),
+        release_date: v
+            .get("releaseDate")
+            .and_then(|v| v.as_str())
+            .map(|v| v.into()),
     }
 }
 
-/// json to JiraIssue
-pub fn as_issue(issue: &Value) -> JiraIssue {
-    let key = issue["key"].as_str().expect("key must not null");
+/// json array to values
+fn as_values<T>(value: &Value, f: impl Fn(&Map<String, Value>) -> T) -> Vec<T> {
+    value
+        .as_array()
+        .map(|v| v.iter().filter_map(|v| v.as_object()).map(&f).collect())
+        .unwrap_or_default()
+}
+
+/// json to JiraIssue. Story points are read from `story_points_field`
+pub fn as_issue(issue: &Value, story_points_field: &str) -> Result<JiraIssue, ApiError> {
+    let key = issue["key"]
+        .as_str()
+        .ok_or(ApiError::InvalidResponse(String::from(
+            "key of issue is not found",
+        )))?;
 
-    JiraIssue {
+    Ok(JiraIssue {
         key: key.to_string(),
         summary: issue["fields"]["summary"]
             .as_str()
-            .expect("summary must not null")
-            .into(),
+            .map(|v| v.into())
+            .unwrap_or_default(),
+        // REST API v2 returns description as plain text
         description: issue["fields"]["description"]["text"]
             .as_str()
+            .or(issue["fields"]["description"].as_str())
             .map(|v| v.into()),
         status: issue["fields"]["status"].as_object().map(as_status),
         issue_type: issue["fields"]["issuetype"].as_object().map(as_issue_type),
@@ -157,5 +365,20 @@ pub fn as_issue(issue: &Value) -> JiraIssue {
             .as_array()
             .map(|v| as_subtasks(v))
             .unwrap_or_default(),
-    }
+        estimate: as_estimate(&issue["fields"], story_points_field),
+        assignee: issue["fields"]["assignee"].as_object().map(as_user),
+        reporter: issue["fields"]["reporter"].as_object().map(as_user),
+        priority: issue["fields"]["priority"].as_object().map(as_priority),
+        labels: issue["fields"]["labels"]
+            .as_array()
+            .map(|v| {
+                v.iter()
+                    .filter_map(|v| v.as_str().map(|v| v.into()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        components: as_values(&issue["fields"]["components"], as_component),
+        fix_versions: as_values(&issue["fields"]["fixVersions"], as_version),
+        parent: issue["fields"]["parent"].as_object().and_then(as_parent),
+    })
 }
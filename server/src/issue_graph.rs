@@ -0,0 +1,327 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::issue::{JiraIssue, JiraIssueLink};
+
+/// A loop of issues that depend on each other through links
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueCycle {
+    pub issues: Vec<String>,
+    pub links: Vec<String>,
+}
+
+/// Order of issues that can be started without waiting other issues in later layers
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueLayers {
+    pub order: Vec<String>,
+    pub layers: BTreeMap<String, usize>,
+}
+
+/// The longest weighted chain of issues and slack of each issue against it
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CriticalPath {
+    pub path: Vec<String>,
+    pub duration: f64,
+    pub slack: BTreeMap<String, f64>,
+}
+
+/// directed graph of issues. An edge goes from the inward issue of a link to the outward issue.
+pub struct IssueGraph {
+    keys: Vec<String>,
+    edges: Vec<Vec<(usize, String)>>,
+}
+
+/// names of link types treated as dependency if no type is given. Other types such as "Relates"
+/// do not mean order of issues
+pub const DEFAULT_LINK_TYPES: [&str; 1] = ["Blocks"];
+
+impl IssueGraph {
+    /// build graph from loaded issues with all links. Links are de-duplicated by their id.
+    pub fn new(issues: &[JiraIssue]) -> IssueGraph {
+        IssueGraph::with_links(issues, |_| true)
+    }
+
+    /// build graph from loaded issues with links that have one of given type names.
+    /// Links of `DEFAULT_LINK_TYPES` are used if `link_types` is empty.
+    pub fn with_link_types(issues: &[JiraIssue], link_types: &[String]) -> IssueGraph {
+        IssueGraph::with_links(issues, |link| {
+            link.link_type.as_ref().is_some_and(|v| {
+                if link_types.is_empty() {
+                    DEFAULT_LINK_TYPES.contains(&v.name.as_str())
+                } else {
+                    link_types.contains(&v.name)
+                }
+            })
+        })
+    }
+
+    /// build graph from loaded issues with links matched with `filter`
+    fn with_links(issues: &[JiraIssue], filter: impl Fn(&JiraIssueLink) -> bool) -> IssueGraph {
+        let links: BTreeMap<&str, &JiraIssueLink> = issues
+            .iter()
+            .flat_map(|issue| issue.links.iter())
+            .filter(|link| filter(link))
+            .map(|link| (link.id.as_str(), link))
+            .collect();
+
+        let mut nodes: BTreeSet<&str> = issues.iter().map(|v| v.key.as_str()).collect();
+        links.values().for_each(|link| {
+            nodes.insert(&link.inward_issue);
+            nodes.insert(&link.outward_issue);
+        });
+
+        let keys: Vec<String> = nodes.into_iter().map(|v| v.to_string()).collect();
+        let indices: HashMap<&str, usize> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (v.as_str(), i))
+            .collect();
+
+        let mut edges = vec![Vec::new(); keys.len()];
+        links.values().for_each(|link| {
+            let from = indices[link.inward_issue.as_str()];
+            let to = indices[link.outward_issue.as_str()];
+            edges[from].push((to, link.id.clone()));
+        });
+
+        IssueGraph { keys, edges }
+    }
+
+    /// get strongly connected components with Tarjan's algorithm.
+    /// Components are returned in reverse topological order.
+    fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let size = self.keys.len();
+        let mut index: Vec<Option<usize>> = vec![None; size];
+        let mut lowlink = vec![0; size];
+        let mut on_stack = vec![false; size];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..size {
+            if index[root].is_some() {
+                continue;
+            }
+
+            index[root] = Some(next_index);
+            lowlink[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            // pair of node and position of the next edge to visit
+            let mut call_stack = vec![(root, 0)];
+
+            while let Some((node, edge)) = call_stack.pop() {
+                if let Some((next, _)) = self.edges[node].get(edge) {
+                    let next = *next;
+                    call_stack.push((node, edge + 1));
+
+                    match index[next] {
+                        None => {
+                            index[next] = Some(next_index);
+                            lowlink[next] = next_index;
+                            next_index += 1;
+                            stack.push(next);
+                            on_stack[next] = true;
+                            call_stack.push((next, 0));
+                        }
+                        Some(visited) if on_stack[next] => {
+                            lowlink[node] = lowlink[node].min(visited);
+                        }
+                        _ => (),
+                    }
+                    continue;
+                }
+
+                if let Some((parent, _)) = call_stack.last() {
+                    lowlink[*parent] = lowlink[*parent].min(lowlink[node]);
+                }
+
+                if index[node] == Some(lowlink[node]) {
+                    let mut component = Vec::new();
+                    while let Some(v) = stack.pop() {
+                        on_stack[v] = false;
+                        component.push(v);
+
+                        if v == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// get all loops in the graph. Each loop contains sorted keys of issues and ids of links in it
+    pub fn cycles(&self) -> Vec<IssueCycle> {
+        let mut cycles: Vec<IssueCycle> = self
+            .strongly_connected_components()
+            .into_iter()
+            .filter_map(|component| {
+                let members: BTreeSet<usize> = component.into_iter().collect();
+                let mut links: Vec<String> = members
+                    .iter()
+                    .flat_map(|from| self.edges[*from].iter())
+                    .filter(|(to, _)| members.contains(to))
+                    .map(|(_, id)| id.clone())
+                    .collect();
+
+                if links.is_empty() {
+                    return None;
+                }
+                links.sort();
+
+                Some(IssueCycle {
+                    issues: members.iter().map(|v| self.keys[*v].clone()).collect(),
+                    links,
+                })
+            })
+            .collect();
+
+        cycles.sort_by(|o1, o2| o1.issues.cmp(&o2.issues));
+        cycles
+    }
+
+    /// get components of the graph and index of component for each node
+    fn condensation(&self) -> (Vec<Vec<usize>>, Vec<usize>) {
+        let components = self.strongly_connected_components();
+        let mut component_of = vec![0; self.keys.len()];
+        components.iter().enumerate().for_each(|(i, component)| {
+            component.iter().for_each(|v| component_of[*v] = i);
+        });
+
+        (components, component_of)
+    }
+
+    /// get topological order and layer of each issue. Layer is the length of the longest path
+    /// from a root issue. Issues in the same loop share a layer.
+    pub fn layers(&self) -> IssueLayers {
+        let (components, component_of) = self.condensation();
+
+        // components are in reverse topological order, so visit them from the last
+        let mut component_layers = vec![0; components.len()];
+        for (i, component) in components.iter().enumerate().rev() {
+            for node in component {
+                for (to, _) in &self.edges[*node] {
+                    let next = component_of[*to];
+
+                    if next != i {
+                        component_layers[next] =
+                            component_layers[next].max(component_layers[i] + 1);
+                    }
+                }
+            }
+        }
+
+        let layers: BTreeMap<String, usize> = self
+            .keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key.clone(), component_layers[component_of[i]]))
+            .collect();
+        let mut order: Vec<String> = self.keys.clone();
+        order.sort_by_key(|v| layers[v]);
+
+        IssueLayers { order, layers }
+    }
+
+    /// get critical path with weights of issues. Issues in the same loop are treated as one
+    /// chunk of work that has sum of their weights.
+    pub fn critical_path(&self, weights: &HashMap<String, f64>) -> CriticalPath {
+        let (components, component_of) = self.condensation();
+        let component_weights: Vec<f64> = components
+            .iter()
+            .map(|component| {
+                component
+                    .iter()
+                    .map(|v| weights.get(&self.keys[*v]).copied().unwrap_or_default())
+                    .sum()
+            })
+            .collect();
+
+        let mut successors: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); components.len()];
+        components.iter().enumerate().for_each(|(i, component)| {
+            component.iter().for_each(|node| {
+                self.edges[*node]
+                    .iter()
+                    .map(|(to, _)| component_of[*to])
+                    .filter(|next| *next != i)
+                    .for_each(|next| {
+                        successors[i].insert(next);
+                    });
+            });
+        });
+
+        // forward pass: earliest finish of each component from the first in topological order
+        let mut earliest_start = vec![0.0; components.len()];
+        let mut previous: Vec<Option<usize>> = vec![None; components.len()];
+        for i in (0..components.len()).rev() {
+            let finish = earliest_start[i] + component_weights[i];
+
+            for next in &successors[i] {
+                if previous[*next].is_none() || earliest_start[*next] < finish {
+                    earliest_start[*next] = finish;
+                    previous[*next] = Some(i);
+                }
+            }
+        }
+        let earliest_finish: Vec<f64> = earliest_start
+            .iter()
+            .zip(component_weights.iter())
+            .map(|(start, weight)| start + weight)
+            .collect();
+        let duration = earliest_finish.iter().copied().fold(0.0, f64::max);
+
+        // backward pass: latest finish of each component not to delay whole duration
+        let mut latest_finish = vec![duration; components.len()];
+        for i in 0..components.len() {
+            latest_finish[i] = successors[i]
+                .iter()
+                .map(|next| latest_finish[*next] - component_weights[*next])
+                .fold(duration, f64::min);
+        }
+
+        let slack = self
+            .keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| {
+                let component = component_of[i];
+                (
+                    key.clone(),
+                    latest_finish[component] - earliest_finish[component],
+                )
+            })
+            .collect();
+
+        let mut path = Vec::new();
+        let mut current = (0..components.len())
+            .filter(|v| earliest_finish[*v] == duration)
+            .min_by_key(|v| components[*v].iter().min().copied());
+        while let Some(component) = current {
+            let mut keys: Vec<String> = components[component]
+                .iter()
+                .map(|v| self.keys[*v].clone())
+                .collect();
+            keys.sort();
+            keys.reverse();
+            path.extend(keys);
+            current = previous[component];
+        }
+        path.reverse();
+
+        CriticalPath {
+            path,
+            duration,
+            slack,
+        }
+    }
+}
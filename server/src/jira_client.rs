@@ -0,0 +1,206 @@
+use std::{
+    env,
+    future::Future,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use isahc::{
+    error::ErrorKind,
+    http::{Request, StatusCode},
+    AsyncBody, RequestExt, Response,
+};
+
+use crate::error::ApiError;
+
+/// Policy to retry requests to Jira
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// max number of retries after the first request
+    pub max_retries: u32,
+    /// delay before the first retry. It is doubled for each retry
+    pub base_delay: Duration,
+    /// max delay between retries
+    pub max_delay: Duration,
+    /// overall time for requests including retries. It is shared by all requests in
+    /// `with_budget`. Keep it shorter than the timeout of Lambda and API Gateway
+    pub budget: Duration,
+}
+
+static POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            budget: Duration::from_secs(20),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// load policy from `JIRA_RETRY_MAX` and `JIRA_RETRY_BUDGET_SECS` environment variables
+    pub fn from_env() -> RetryPolicy {
+        let default = RetryPolicy::default();
+
+        RetryPolicy {
+            max_retries: env::var("JIRA_RETRY_MAX")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default.max_retries),
+            budget: env::var("JIRA_RETRY_BUDGET_SECS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(default.budget),
+            ..default
+        }
+    }
+
+    /// get policy loaded at the first call
+    pub fn global() -> &'static RetryPolicy {
+        POLICY.get_or_init(RetryPolicy::from_env)
+    }
+
+    /// delay before the retry. Jitter is added to avoid retrying at the same time
+    fn backoff(&self, retry: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let half = delay / 2;
+
+        half + half.mul_f64(fastrand::f64())
+    }
+}
+
+tokio::task_local! {
+    /// deadline shared by all requests in a call of `with_budget`
+    static DEADLINE: Instant;
+}
+
+/// run `f` with the budget of the policy as the deadline of all requests sent in it
+pub async fn with_budget<F: Future>(policy: &RetryPolicy, f: F) -> F::Output {
+    DEADLINE.scope(Instant::now() + policy.budget, f).await
+}
+
+/// true if the status can be recovered by retrying. Non-idempotent requests are retried only if
+/// Jira did not process them
+fn is_retryable(status: StatusCode, idempotent: bool) -> bool {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => true,
+        StatusCode::BAD_GATEWAY | StatusCode::GATEWAY_TIMEOUT => idempotent,
+        _ => false,
+    }
+}
+
+/// true if the error can be recovered by retrying. Non-idempotent requests are retried only if
+/// the connection could not be established
+fn is_retryable_error(error: &isahc::Error, idempotent: bool) -> bool {
+    if idempotent {
+        error.is_network() || error.is_timeout()
+    } else {
+        matches!(
+            error.kind(),
+            ErrorKind::ConnectionFailed | ErrorKind::NameResolution
+        )
+    }
+}
+
+/// send request made by `build` with the global policy. See `send_with_policy`
+pub async fn send<F, B>(build: F) -> Result<Response<AsyncBody>, ApiError>
+where
+    F: Fn() -> Result<Request<B>, isahc::http::Error>,
+    B: Into<AsyncBody>,
+{
+    send_with_policy(RetryPolicy::global(), build).await
+}
+
+/// send non-idempotent request made by `build` with the global policy. See
+/// `send_non_idempotent_with_policy`
+pub async fn send_non_idempotent<F, B>(build: F) -> Result<Response<AsyncBody>, ApiError>
+where
+    F: Fn() -> Result<Request<B>, isahc::http::Error>,
+    B: Into<AsyncBody>,
+{
+    send_non_idempotent_with_policy(RetryPolicy::global(), build).await
+}
+
+/// send request made by `build`, and retry it while Jira is rate limited or unavailable.
+/// Wait for `Retry-After` if Jira gives it, otherwise back off exponentially.
+/// Return error if the response is not successful at last
+pub async fn send_with_policy<F, B>(
+    policy: &RetryPolicy,
+    build: F,
+) -> Result<Response<AsyncBody>, ApiError>
+where
+    F: Fn() -> Result<Request<B>, isahc::http::Error>,
+    B: Into<AsyncBody>,
+{
+    send_retrying(policy, true, build).await
+}
+
+/// same as `send_with_policy`, but do not retry the request if Jira might have processed it,
+/// such as 502 and 504 status
+pub async fn send_non_idempotent_with_policy<F, B>(
+    policy: &RetryPolicy,
+    build: F,
+) -> Result<Response<AsyncBody>, ApiError>
+where
+    F: Fn() -> Result<Request<B>, isahc::http::Error>,
+    B: Into<AsyncBody>,
+{
+    send_retrying(policy, false, build).await
+}
+
+async fn send_retrying<F, B>(
+    policy: &RetryPolicy,
+    idempotent: bool,
+    build: F,
+) -> Result<Response<AsyncBody>, ApiError>
+where
+    F: Fn() -> Result<Request<B>, isahc::http::Error>,
+    B: Into<AsyncBody>,
+{
+    let deadline = DEADLINE
+        .try_with(|v| *v)
+        .unwrap_or_else(|_| Instant::now() + policy.budget);
+    let mut retry = 0;
+
+    loop {
+        let request = build()?;
+        let result = tokio::time::timeout_at(deadline.into(), request.send_async())
+            .await
+            .map_err(|_| ApiError::Network(String::from("deadline of requests exceeded")))?;
+
+        let (error, delay) = match result {
+            Ok(mut res) => match ApiError::from_jira_response(&mut res).await {
+                None => return Ok(res),
+                Some(e) if !is_retryable(res.status(), idempotent) => return Err(e),
+                Some(ApiError::RateLimited {
+                    retry_after: Some(retry_after),
+                }) => (
+                    ApiError::RateLimited {
+                        retry_after: Some(retry_after),
+                    },
+                    Duration::from_secs(retry_after),
+                ),
+                Some(e) => (e, policy.backoff(retry)),
+            },
+            Err(e) if is_retryable_error(&e, idempotent) => {
+                (ApiError::from(e), policy.backoff(retry))
+            }
+            Err(e) => return Err(ApiError::from(e)),
+        };
+
+        if retry >= policy.max_retries || Instant::now() + delay > deadline {
+            return Err(error);
+        }
+
+        tracing::warn!("retry request to Jira after {:?}: {}", delay, error);
+        tokio::time::sleep(delay).await;
+        retry += 1;
+    }
+}
@@ -0,0 +1,34 @@
+use isahc::{AsyncReadResponseExt, Request};
+use serde_json::Value;
+
+use crate::{error::ApiError, jira_client, jira_url::JiraUrl};
+
+// get JQL of saved filter from Jira API
+pub async fn get_filter_jql(id: u64, url: &impl JiraUrl) -> Result<String, ApiError> {
+    let jira_url = url.get_api_url(&format!("filter/{}", id));
+
+    let mut res = jira_client::send(|| {
+        Request::get(&jira_url)
+            .header(
+                "authorization",
+                url.get_base_headers()
+                    .get("authorization")
+                    .unwrap_or(&String::from("")),
+            )
+            .body(())
+    })
+    .await?;
+
+    let json = res.json::<Value>().await?;
+    let jql = json["jql"].as_str().unwrap_or_default().trim();
+
+    // empty JQL matches all issues in Jira. It is too large to load
+    if jql.is_empty() {
+        return Err(ApiError::InvalidRequest(format!(
+            "filter {} does not have JQL",
+            id
+        )));
+    }
+
+    Ok(jql.to_string())
+}
@@ -1,137 +1,589 @@
-use isahc::{ReadResponseExt, Request, RequestExt};
-use serde_json::{json, Value};
-use std::collections::{HashMap, HashSet};
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    env,
+    str::FromStr,
+    sync::OnceLock,
+};
 
-use crate::api_type::IssueLoadingRequest;
-use crate::issue::as_issue;
+use crate::api_type::{IssueConditionLoadingRequest, IssueLoadingRequest, IssueSearchCondition};
+use crate::error::ApiError;
 use crate::issue::JiraIssue;
-use crate::jira_url::JiraUrl;
-
-/// get all keys of issue
-fn as_issue_keys(issue: &Value) -> HashSet<String> {
-    let mut keys = HashSet::new();
-
-    let key = issue["key"]
-        .as_str()
-        .expect("key must not null")
-        .to_string();
-    keys.insert(key);
-
-    if let Some(v) = issue["fields"]["subtasks"].as_array() {
-        v.iter().for_each(|v| {
-            if let Some(key) = v["key"].as_str() {
-                keys.insert(key.to_string());
-            }
-        })
+use crate::issue::{as_issue, issue_fields, DEFAULT_STORY_POINTS_FIELD};
+use crate::jira_filter_request::get_filter_jql;
+use crate::jira_search_request::{search_page, PageCursor, SearchPage, MAX_PAGE_SIZE};
+use crate::jira_sprint_request::resolve_sprint_ids;
+use crate::jira_url::{JiraUrl, SearchApi};
+use crate::jql::{IssueKey, IssueKeyPattern, Jql};
+
+/// Configuration to load issues from Jira
+#[derive(Clone, Debug)]
+pub struct LoadingConfig {
+    /// number of issues requested in a page. Jira limits it to `MAX_PAGE_SIZE`
+    pub page_size: u32,
+    /// number of requests to Jira at the same time. Chunks of keys are requested concurrently,
+    /// and pages only for the scope of issues
+    pub concurrency: usize,
+    /// max number of keys in a JQL. Larger key lists are split into chunks
+    pub chunk_size: usize,
+    /// pattern to validate requested keys
+    pub key_pattern: IssueKeyPattern,
+    /// field id of story points
+    pub story_points_field: String,
+}
+
+impl Default for LoadingConfig {
+    fn default() -> Self {
+        LoadingConfig {
+            page_size: 50,
+            concurrency: 4,
+            chunk_size: 100,
+            key_pattern: IssueKeyPattern::default(),
+            story_points_field: String::from(DEFAULT_STORY_POINTS_FIELD),
+        }
+    }
+}
+
+static CONFIG: OnceLock<LoadingConfig> = OnceLock::new();
+
+/// parse environment variable
+fn parse_env<T: FromStr>(name: &str) -> Option<T> {
+    env::var(name).ok().and_then(|v| v.trim().parse().ok())
+}
+
+impl LoadingConfig {
+    /// load configuration from `JIRA_PAGE_SIZE`, `JIRA_PAGE_CONCURRENCY`, `JIRA_KEY_CHUNK_SIZE`,
+    /// `JIRA_ISSUE_KEY_PATTERN` and `JIRA_STORY_POINTS_FIELD` environment variables
+    pub fn from_env() -> LoadingConfig {
+        let default = LoadingConfig::default();
+
+        LoadingConfig {
+            page_size: parse_env("JIRA_PAGE_SIZE")
+                .unwrap_or(default.page_size)
+                .clamp(1, MAX_PAGE_SIZE),
+            concurrency: parse_env("JIRA_PAGE_CONCURRENCY")
+                .unwrap_or(default.concurrency)
+                .max(1),
+            chunk_size: parse_env("JIRA_KEY_CHUNK_SIZE")
+                .unwrap_or(default.chunk_size)
+                .max(1),
+            key_pattern: IssueKeyPattern::from_env(),
+            story_points_field: parse_env::<String>("JIRA_STORY_POINTS_FIELD")
+                .filter(|v| !v.is_empty())
+                .unwrap_or(default.story_points_field),
+        }
     }
 
-    keys
+    /// get configuration loaded at the first call
+    pub fn global() -> &'static LoadingConfig {
+        CONFIG.get_or_init(LoadingConfig::from_env)
+    }
 }
 
-fn request_to_jql(request: &IssueLoadingRequest) -> String {
-    let keys = request.issues.join(",");
-    format!("key in ({})", keys)
+/// keys that could not be loaded with the reason
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChunkFailure {
+    pub keys: Vec<IssueKey>,
+    #[serde(flatten)]
+    pub error: ApiError,
 }
 
-/// load all issues from Jira API with JQL
-fn load_issue_recursive<T, F>(
+/// Report of issues that could not be loaded
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadingReport {
+    /// requested keys that Jira did not return. They may be deleted or not permitted
+    pub missing_keys: Vec<IssueKey>,
+    /// chunks of keys failed to load
+    pub warnings: Vec<ChunkFailure>,
+    /// true if following links is stopped by max number of issues
+    pub truncated: bool,
+}
+
+/// issues loaded with request
+#[derive(Clone, Debug)]
+pub struct LoadedIssues {
+    pub issues: Vec<JiraIssue>,
+    pub report: LoadingReport,
+}
+
+/// load all issues with offset paging. Pages after the first one are requested with
+/// `page_concurrency` at the same time
+async fn load_issue_by_offset(
     jql: &str,
+    page_concurrency: usize,
+    config: &LoadingConfig,
     url: &impl JiraUrl,
-    callback: &mut F,
-) -> Result<(), Box<dyn std::error::Error>>
-where
-    F: FnMut(&mut Value) -> T,
-    T: Clone,
-{
-    let mut total = Some(0);
-
-    while let Some(current_total) = total {
-        let jira_url = url.get_url("/rest/api/3/search");
-        let body = json!({
-            "jql": jql,
-            "startAt": current_total,
-            "maxResults": 50,
-            "fields": vec!["status", "issuetype", "issuelinks", "subtasks", "summary"]
-        });
-
-        let mut res = Request::post(jira_url)
-            .header(
-                "authorization",
-                url.get_base_headers()
-                    .get("authorization")
-                    .unwrap_or(&String::from("")),
-            )
-            .header("content-type", "application/json")
-            .body(body.to_string())?
-            .send()?;
-
-        let json: Value = res.json()?;
-
-        match json["issues"].as_array() {
+) -> Result<Vec<Value>, ApiError> {
+    let page_size = config.page_size as usize;
+    let fields = issue_fields(&config.story_points_field);
+    let first_page = search_page(jql, &PageCursor::Offset(0), page_size, &fields, url).await?;
+    let mut issues = first_page.issues;
+
+    // Jira can return fewer issues than requested, so use the size of the first page as step
+    let step = issues.len();
+    let total = first_page.total.unwrap_or_default();
+
+    if step == 0 || total <= step {
+        return Ok(issues);
+    }
+
+    let fields = &fields;
+    let pages: Vec<SearchPage> = stream::iter((step..total).step_by(step))
+        .map(|start_at| async move {
+            search_page(jql, &PageCursor::Offset(start_at), page_size, fields, url).await
+        })
+        .buffered(page_concurrency.max(1))
+        .try_collect()
+        .await?;
+
+    issues.extend(pages.into_iter().flat_map(|v| v.issues));
+
+    Ok(issues)
+}
+
+/// load all issues with token paging. Pages are requested one by one because each page
+/// gives the token of the next page
+async fn load_issue_by_token(
+    jql: &str,
+    config: &LoadingConfig,
+    url: &impl JiraUrl,
+) -> Result<Vec<Value>, ApiError> {
+    let mut issues = Vec::new();
+    let mut cursor = PageCursor::Token(None);
+    let fields = issue_fields(&config.story_points_field);
+
+    loop {
+        let page = search_page(jql, &cursor, config.page_size as usize, &fields, url).await?;
+        issues.extend(page.issues);
+
+        match page.next_page_token {
+            Some(token) => cursor = PageCursor::Token(Some(token)),
             None => break,
-            Some(got_issues) => {
-                let total_size = json["total"].as_u64().unwrap_or_default() as usize;
+        }
+    }
 
-                got_issues.clone().iter_mut().for_each(|v| {
-                    (*callback)(v);
-                });
+    Ok(issues)
+}
 
-                let new_total = current_total + got_issues.len();
+/// load all issues from Jira API with JQL. Token paging ignores `page_concurrency` because pages
+/// can not be requested before the previous one
+async fn load_issue_recursive(
+    jql: &str,
+    page_concurrency: usize,
+    config: &LoadingConfig,
+    url: &impl JiraUrl,
+) -> Result<Vec<Value>, ApiError> {
+    match url.search_api() {
+        SearchApi::Offset => load_issue_by_offset(jql, page_concurrency, config, url).await,
+        SearchApi::Jql => load_issue_by_token(jql, config, url).await,
+    }
+}
 
-                if total_size <= new_total {
-                    total = None;
-                } else {
-                    total = Some(new_total);
-                }
+/// load issues of a chunk of keys. Pages are requested one by one, because chunks are already
+/// requested concurrently and requests to Jira must be kept in `concurrency`.
+///
+/// Jira rejects the whole JQL if one of keys does not exist, so rejected keys are split and
+/// requested again. A rejected single key is dropped to be reported as a missing key
+async fn load_chunk(
+    chunk: Vec<IssueKey>,
+    config: &LoadingConfig,
+    url: &impl JiraUrl,
+) -> Result<Vec<Value>, ChunkFailure> {
+    let mut pending = vec![chunk.clone()];
+    let mut issues = Vec::new();
+
+    while let Some(keys) = pending.pop() {
+        match load_issue_recursive(&Jql::new().keys(&keys).to_string(), 1, config, url).await {
+            Ok(values) => issues.extend(values),
+            Err(ApiError::InvalidRequest(_)) if keys.len() > 1 => {
+                let (first, second) = keys.split_at(keys.len() / 2);
+                pending.push(second.to_vec());
+                pending.push(first.to_vec());
             }
+            Err(ApiError::InvalidRequest(_)) => (),
+            Err(error) => return Err(ChunkFailure { keys: chunk, error }),
         }
     }
 
-    Ok(())
+    Ok(issues)
 }
 
-// load issue with request
-pub fn load_issue(request: &IssueLoadingRequest, url: impl JiraUrl) -> Vec<JiraIssue> {
-    let mut loaded_issues = HashMap::new();
-    let mut issue_keys: HashSet<String> = HashSet::new();
-    let jql = request_to_jql(request);
-    let mut callback = |value: &mut Value| {
-        for v in as_issue_keys(value) {
-            issue_keys.insert(v);
+/// load issues of keys. Keys are split into chunks to keep JQL in the limit of Jira, and
+/// failed chunks are returned with loaded issues
+async fn load_keys(
+    keys: &[IssueKey],
+    config: &LoadingConfig,
+    url: &impl JiraUrl,
+) -> (Vec<Value>, Vec<ChunkFailure>) {
+    let chunks: Vec<Vec<IssueKey>> = keys
+        .chunks(config.chunk_size.max(1))
+        .map(|v| v.to_vec())
+        .collect();
+    let results: Vec<Result<Vec<Value>, ChunkFailure>> = stream::iter(chunks)
+        .map(|chunk| load_chunk(chunk, config, url))
+        .buffer_unordered(config.concurrency.max(1))
+        .collect()
+        .await;
+
+    let mut issues = Vec::new();
+    let mut failures = Vec::new();
+    for result in results {
+        match result {
+            Ok(values) => issues.extend(values),
+            Err(failure) => failures.push(failure),
         }
+    }
 
-        loaded_issues.insert(
-            value["key"].as_str().unwrap_or_default().to_string(),
-            as_issue(value),
-        );
-    };
+    (issues, failures)
+}
 
-    // load all issue keys in jql
-    load_issue_recursive(&jql, &url, &mut callback).unwrap_or(());
+/// insert issues parsed from json into `loaded_issues`. Malformed issues are skipped and returned
+/// as failures without keys
+fn insert_issues(
+    values: Vec<Value>,
+    config: &LoadingConfig,
+    loaded_issues: &mut HashMap<String, JiraIssue>,
+) -> Vec<ChunkFailure> {
+    let mut failures = Vec::new();
 
-    let binding = HashSet::from_iter(loaded_issues.keys().into_iter().map(|v| v.to_string()));
-    let not_full_loaded_keys = issue_keys
-        .difference(&binding)
-        .map(|v| v.to_string())
-        .collect::<Vec<String>>();
+    for value in values {
+        match as_issue(&value, &config.story_points_field) {
+            Ok(issue) => {
+                loaded_issues.insert(issue.key.clone(), issue);
+            }
+            Err(error) => failures.push(ChunkFailure {
+                keys: Vec::new(),
+                error,
+            }),
+        }
+    }
 
-    if !not_full_loaded_keys.is_empty() {
-        let jql = format!("key IN ({})", not_full_loaded_keys.join(","));
+    failures
+}
 
-        // load issues do not fully-loaded
-        // re-define to avoid borrow-checker
-        let mut callback = |value: &mut Value| {
-            loaded_issues.insert(
-                value["key"].as_str().unwrap_or_default().to_string(),
-                as_issue(value),
-            );
-        };
+/// insert loaded issues into `loaded_issues`, and load subtasks of them that are not loaded yet.
+/// Subtasks are loaded until `loaded_issues` reaches `max_issues`. Return failed chunks, and true
+/// if some subtasks are skipped by `max_issues`
+async fn insert_with_subtasks(
+    values: Vec<Value>,
+    max_issues: Option<usize>,
+    config: &LoadingConfig,
+    url: &impl JiraUrl,
+    loaded_issues: &mut HashMap<String, JiraIssue>,
+) -> (Vec<ChunkFailure>, bool) {
+    let mut inserted = HashMap::new();
+    let mut failures = insert_issues(values, config, &mut inserted);
+    let issue_keys = inserted
+        .values()
+        .flat_map(|issue| issue.subtasks.iter().chain([&issue.key]))
+        .cloned()
+        .collect::<HashSet<String>>();
+    loaded_issues.extend(inserted);
+
+    let capacity = max_issues
+        .map(|v| v.saturating_sub(loaded_issues.len()))
+        .unwrap_or(usize::MAX);
+    let candidates = issue_keys
+        .into_iter()
+        .filter(|v| !loaded_issues.contains_key(v))
+        .collect::<BTreeSet<String>>();
+    let not_full_loaded_keys = candidates
+        .iter()
+        .take(capacity)
+        .map(|v| IssueKey::from_jira(v))
+        .collect::<Vec<IssueKey>>();
+    let truncated = not_full_loaded_keys.len() < candidates.len();
 
-        load_issue_recursive(&jql, &url, &mut callback).unwrap_or(());
+    if not_full_loaded_keys.is_empty() {
+        return (failures, truncated);
     }
 
+    // load issues do not fully-loaded
+    let (values, subtask_failures) = load_keys(&not_full_loaded_keys, config, url).await;
+    failures.extend(subtask_failures);
+    failures.extend(insert_issues(values, config, loaded_issues));
+
+    (failures, truncated)
+}
+
+/// load issues of keys and subtasks of them into `loaded_issues`. See `insert_with_subtasks`
+async fn load_issue_with_subtasks(
+    keys: &[IssueKey],
+    max_issues: Option<usize>,
+    config: &LoadingConfig,
+    url: &impl JiraUrl,
+    loaded_issues: &mut HashMap<String, JiraIssue>,
+) -> (Vec<ChunkFailure>, bool) {
+    let (values, mut failures) = load_keys(keys, config, url).await;
+    let (subtask_failures, truncated) =
+        insert_with_subtasks(values, max_issues, config, url, loaded_issues).await;
+    failures.extend(subtask_failures);
+
+    (failures, truncated)
+}
+
+/// get keys of linked issues that are not loaded yet
+fn as_linked_keys(loaded_issues: &HashMap<String, JiraIssue>) -> BTreeSet<String> {
     loaded_issues
         .values()
-        .map(|issue| issue.correct_from_map(&loaded_issues))
+        .flat_map(|issue| issue.links.iter())
+        .flat_map(|link| [&link.inward_issue, &link.outward_issue])
+        .filter(|key| !loaded_issues.contains_key(*key))
+        .cloned()
         .collect()
 }
+
+/// load parents of loaded issues until the top of hierarchy. Return true if it is stopped by
+/// max number of issues
+async fn load_parents(
+    max_issues: Option<usize>,
+    requested_keys: &mut BTreeSet<String>,
+    loaded_issues: &mut HashMap<String, JiraIssue>,
+    warnings: &mut Vec<ChunkFailure>,
+    config: &LoadingConfig,
+    url: &impl JiraUrl,
+) -> bool {
+    loop {
+        let capacity = max_issues
+            .map(|v| v.saturating_sub(loaded_issues.len()))
+            .unwrap_or(usize::MAX);
+        let candidates = loaded_issues
+            .values()
+            .filter_map(|issue| issue.parent.as_ref())
+            .map(|parent| parent.key.clone())
+            .filter(|key| !loaded_issues.contains_key(key) && !requested_keys.contains(key))
+            .collect::<BTreeSet<String>>();
+        let parent_keys = candidates
+            .iter()
+            .take(capacity)
+            .map(|v| IssueKey::from_jira(v))
+            .collect::<Vec<IssueKey>>();
+
+        if parent_keys.is_empty() {
+            return !candidates.is_empty();
+        }
+
+        // subtasks of parents are not loaded, because they are siblings of loaded issues
+        requested_keys.extend(parent_keys.iter().map(|v| v.to_string()));
+        let (values, failures) = load_keys(&parent_keys, config, url).await;
+        warnings.extend(failures);
+        warnings.extend(insert_issues(values, config, loaded_issues));
+
+        if parent_keys.len() < candidates.len() {
+            return true;
+        }
+    }
+}
+
+// load issue with request
+pub async fn load_issue(
+    request: &IssueLoadingRequest,
+    url: impl JiraUrl,
+) -> Result<LoadedIssues, ApiError> {
+    load_issue_with_config(request, LoadingConfig::global(), url).await
+}
+
+// load issue with request and configuration
+pub async fn load_issue_with_config(
+    request: &IssueLoadingRequest,
+    config: &LoadingConfig,
+    url: impl JiraUrl,
+) -> Result<LoadedIssues, ApiError> {
+    let keys = IssueKey::parse_all(&request.issues, &config.key_pattern)?;
+    let scope = with_filter(Jql::new(), request, &url).await?;
+
+    load_issue_in_scope(request, &keys, &scope, config, &url).await
+}
+
+/// add JQL of saved filter in the request to scope
+async fn with_filter(
+    scope: Jql,
+    request: &IssueLoadingRequest,
+    url: &impl JiraUrl,
+) -> Result<Jql, ApiError> {
+    match request.filter {
+        Some(id) => Ok(scope.and_jql(&get_filter_jql(id, url).await?)),
+        None => Ok(scope),
+    }
+}
+
+/// make JQL of issues in the scope of condition. Issues in epic are children of it in
+/// team-managed projects, and linked with `Epic Link` in company-managed projects
+async fn condition_to_jql(
+    condition: &IssueSearchCondition,
+    config: &LoadingConfig,
+    url: &impl JiraUrl,
+) -> Result<Jql, ApiError> {
+    let epic = condition.epic.as_deref().filter(|v| !v.trim().is_empty());
+    let sprint = condition.sprint.as_deref().filter(|v| !v.trim().is_empty());
+    let mut jql = Jql::new();
+
+    if epic.is_none() && sprint.is_none() {
+        return Err(ApiError::InvalidRequest(String::from(
+            "sprint or epic is required",
+        )));
+    }
+
+    if let Some(epic) = epic {
+        let epic = IssueKey::parse(epic, &config.key_pattern)?;
+        jql = jql.any_equals(&["parent", "\"Epic Link\""], epic.as_str());
+    }
+
+    if let Some(sprint) = sprint {
+        let ids = resolve_sprint_ids(sprint, url).await?;
+        jql = jql.in_ids("sprint", &ids);
+    }
+
+    Ok(jql)
+}
+
+// load issues in sprint or epic with request
+pub async fn load_issue_by_condition(
+    request: &IssueConditionLoadingRequest,
+    url: impl JiraUrl,
+) -> Result<LoadedIssues, ApiError> {
+    load_issue_by_condition_with_config(request, LoadingConfig::global(), url).await
+}
+
+// load issues in sprint or epic with request and configuration. Issues given in the request are
+// loaded together
+pub async fn load_issue_by_condition_with_config(
+    request: &IssueConditionLoadingRequest,
+    config: &LoadingConfig,
+    url: impl JiraUrl,
+) -> Result<LoadedIssues, ApiError> {
+    let keys = IssueKey::parse_all(&request.request.issues, &config.key_pattern)?;
+    let scope = condition_to_jql(&request.condition, config, &url).await?;
+    let scope = with_filter(scope, &request.request, &url).await?;
+
+    load_issue_in_scope(&request.request, &keys, &scope, config, &url).await
+}
+
+/// load issues matched with scope and issues of keys with subtasks of them, then follow links.
+/// Empty scope loads issues of keys only
+async fn load_issue_in_scope(
+    request: &IssueLoadingRequest,
+    keys: &[IssueKey],
+    scope: &Jql,
+    config: &LoadingConfig,
+    url: &impl JiraUrl,
+) -> Result<LoadedIssues, ApiError> {
+    let mut loaded_issues = HashMap::new();
+    let mut requested_keys: BTreeSet<String> = keys.iter().map(|v| v.to_string()).collect();
+    let mut warnings = Vec::new();
+
+    if !scope.is_empty() {
+        let values =
+            load_issue_recursive(&scope.to_string(), config.concurrency, config, url).await?;
+        requested_keys.extend(
+            values
+                .iter()
+                .filter_map(|v| v["key"].as_str().map(|v| v.to_string())),
+        );
+        let (failures, _) =
+            insert_with_subtasks(values, None, config, url, &mut loaded_issues).await;
+        warnings.extend(failures);
+    }
+
+    let (failures, _) = load_issue_with_subtasks(keys, None, config, url, &mut loaded_issues).await;
+    warnings.extend(failures);
+
+    follow_links(
+        request,
+        requested_keys,
+        loaded_issues,
+        warnings,
+        config,
+        url,
+    )
+    .await
+}
+
+/// follow links from loaded issues breadth-first, and report issues that could not be loaded.
+/// `requested_keys` are keys requested already, to avoid requesting issues that can not be
+/// loaded again
+async fn follow_links(
+    request: &IssueLoadingRequest,
+    mut requested_keys: BTreeSet<String>,
+    mut loaded_issues: HashMap<String, JiraIssue>,
+    mut warnings: Vec<ChunkFailure>,
+    config: &LoadingConfig,
+    url: &impl JiraUrl,
+) -> Result<LoadedIssues, ApiError> {
+    // nothing to return if all requested issues are failed
+    if loaded_issues.is_empty() && !warnings.is_empty() {
+        return Err(warnings.swap_remove(0).error);
+    }
+
+    let mut truncated = false;
+    for _ in 0..request.depth {
+        let capacity = request
+            .max_issues
+            .map(|v| v.saturating_sub(loaded_issues.len()))
+            .unwrap_or(usize::MAX);
+        let candidates = as_linked_keys(&loaded_issues)
+            .into_iter()
+            .filter(|v| !requested_keys.contains(v))
+            .collect::<Vec<String>>();
+        let linked_keys = candidates
+            .iter()
+            .take(capacity)
+            .map(|v| IssueKey::from_jira(v))
+            .collect::<Vec<IssueKey>>();
+
+        truncated = linked_keys.len() < candidates.len();
+        if linked_keys.is_empty() {
+            break;
+        }
+
+        requested_keys.extend(linked_keys.iter().map(|v| v.to_string()));
+        // subtasks of linked issues are counted against max number of issues
+        let (failures, subtasks_truncated) = load_issue_with_subtasks(
+            &linked_keys,
+            request.max_issues,
+            config,
+            url,
+            &mut loaded_issues,
+        )
+        .await;
+        warnings.extend(failures);
+        truncated = truncated || subtasks_truncated;
+
+        if truncated {
+            break;
+        }
+    }
+
+    if request.load_parents && !truncated {
+        truncated = load_parents(
+            request.max_issues,
+            &mut requested_keys,
+            &mut loaded_issues,
+            &mut warnings,
+            config,
+            url,
+        )
+        .await;
+    }
+
+    let missing_keys = requested_keys
+        .iter()
+        .filter(|v| !loaded_issues.contains_key(*v))
+        .map(|v| IssueKey::from_jira(v))
+        .collect();
+
+    Ok(LoadedIssues {
+        issues: loaded_issues
+            .values()
+            .map(|issue| issue.correct_from_map(&loaded_issues))
+            .collect(),
+        report: LoadingReport {
+            missing_keys,
+            warnings,
+            truncated,
+        },
+    })
+}
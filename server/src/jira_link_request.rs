@@ -1,97 +1,120 @@
-use isahc::{http::StatusCode, Body, Error, Request, RequestExt, Response};
-use serde_json::json;
+use isahc::{AsyncBody, AsyncReadResponseExt, Request, Response};
+use serde_json::{json, Value};
 use url::Url;
 
-use crate::{issue::JiraIssueLink, jira_url::JiraUrl};
+use crate::{
+    api_type::IssueLinkTypeRef,
+    error::ApiError,
+    issue::{as_issue_link_type, JiraIssueLink, JiraIssueLinkType},
+    jira_client,
+    jira_url::JiraUrl,
+};
 
 // load all sprints from Jira API
-fn request_create_link(
+async fn request_create_link(
     inward_key: &str,
     outward_key: &str,
+    link_type: &IssueLinkTypeRef,
     url: &impl JiraUrl,
-) -> Result<Response<Body>, Error> {
-    Request::post(url.get_url("/rest/api/3/issueLink"))
-        .header(
-            "authorization",
-            url.get_base_headers()
-                .get("authorization")
-                .unwrap_or(&String::from("")),
-        )
-        .header("content-type", "application/json")
-        .body(
-            json!({
-                "outwardIssue": {
-                    "key": outward_key,
-                },
-                "inwardIssue": {
-                    "key": inward_key
-                },
-                "type": {
-                    "name": "Blocks"
-                }
-            })
-            .to_string(),
-        )?
-        .send()
+) -> Result<Response<AsyncBody>, ApiError> {
+    let body = json!({
+        "outwardIssue": {
+            "key": outward_key,
+        },
+        "inwardIssue": {
+            "key": inward_key
+        },
+        "type": link_type
+    });
+
+    jira_client::send_non_idempotent(|| {
+        Request::post(url.get_api_url("issueLink"))
+            .header(
+                "authorization",
+                url.get_base_headers()
+                    .get("authorization")
+                    .unwrap_or(&String::from("")),
+            )
+            .header("content-type", "application/json")
+            .body(body.to_string())
+    })
+    .await
+}
+
+// get type of the link
+async fn request_link_type(id: &str, url: &impl JiraUrl) -> Option<JiraIssueLinkType> {
+    let jira_url = url.get_api_url(&format!("issueLink/{}", id));
+
+    let json: Value = jira_client::send(|| {
+        Request::get(&jira_url)
+            .header(
+                "authorization",
+                url.get_base_headers()
+                    .get("authorization")
+                    .unwrap_or(&String::from("")),
+            )
+            .body(())
+    })
+    .await
+    .ok()?
+    .json()
+    .await
+    .ok()?;
+
+    json["type"].as_object().map(as_issue_link_type)
 }
 
 // create link between two issues
-pub fn create_link(
+pub async fn create_link(
     inward_key: &str,
     outward_key: &str,
+    link_type: &IssueLinkTypeRef,
     url: &impl JiraUrl,
-) -> Option<JiraIssueLink> {
-    if let Ok(res) = request_create_link(inward_key, outward_key, url) {
-        let status = res.status();
+) -> Result<JiraIssueLink, ApiError> {
+    let res = request_create_link(inward_key, outward_key, link_type, url).await?;
 
-        if let StatusCode::CREATED = status {
-            let location = res.headers().get("location").unwrap().to_str().unwrap();
-            let url = Url::parse(location).expect("should be URL as location");
-            let id_segment = url
-                .path_segments()
-                .map(|c| c.collect::<Vec<_>>())
-                .and_then(|c| c.last().cloned())
-                .expect("should has least one segment");
+    let id_segment = res
+        .headers()
+        .get("location")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Url::parse(v).ok())
+        .and_then(|v| v.path_segments()?.next_back().map(|v| v.to_string()))
+        .ok_or(ApiError::InvalidResponse(String::from(
+            "location of created link is not found",
+        )))?;
 
-            Some(JiraIssueLink {
-                id: id_segment.to_string(),
-                outward_issue: String::from(outward_key),
-                inward_issue: String::from(inward_key),
-            })
-        } else {
-            None
-        }
-    } else {
-        None
-    }
+    Ok(JiraIssueLink {
+        link_type: request_link_type(&id_segment, url).await,
+        id: id_segment,
+        outward_issue: String::from(outward_key),
+        inward_issue: String::from(inward_key),
+    })
 }
 
 // load all sprints from Jira API
-fn request_delete_link(id: &str, url: &impl JiraUrl) -> Result<Response<Body>, Error> {
-    let jira_url = url.get_url(&format!("/rest/api/3/issueLink/{}", id));
+async fn request_delete_link(
+    id: &str,
+    url: &impl JiraUrl,
+) -> Result<Response<AsyncBody>, ApiError> {
+    let jira_url = url.get_api_url(&format!("issueLink/{}", id));
 
-    Request::delete(jira_url)
-        .header(
-            "authorization",
-            url.get_base_headers()
-                .get("authorization")
-                .unwrap_or(&String::from("")),
-        )
-        .header("content-type", "application/json")
-        .body(())?
-        .send()
+    jira_client::send(|| {
+        Request::delete(&jira_url)
+            .header(
+                "authorization",
+                url.get_base_headers()
+                    .get("authorization")
+                    .unwrap_or(&String::from("")),
+            )
+            .header("content-type", "application/json")
+            .body(())
+    })
+    .await
 }
 
-// create link between two issues
-pub fn delete_link(id: &str, url: &impl JiraUrl) -> Option<()> {
-    if let Ok(res) = request_delete_link(id, url) {
-        let status = res.status();
+// delete link between two issues
+pub async fn delete_link(id: &str, url: &impl JiraUrl) -> Result<(), ApiError> {
+    request_delete_link(id, url).await?;
 
-        match status {
-            StatusCode::OK | StatusCode::NO_CONTENT => Some(()),
-            _ => None,
-        }
-    } else {
-        None
-    }
+    Ok(())
 }
@@ -0,0 +1,37 @@
+use isahc::{AsyncReadResponseExt, Request};
+
+use serde_json::Value;
+
+use crate::{
+    error::ApiError,
+    issue::{as_issue_link_type, JiraIssueLinkType},
+    jira_client,
+    jira_url::JiraUrl,
+};
+
+// load all link types from Jira API
+pub async fn get_link_types(url: impl JiraUrl) -> Result<Vec<JiraIssueLinkType>, ApiError> {
+    let jira_url = url.get_api_url("issueLinkType");
+
+    let mut res = jira_client::send(|| {
+        Request::get(&jira_url)
+            .header(
+                "authorization",
+                url.get_base_headers()
+                    .get("authorization")
+                    .unwrap_or(&String::from("")),
+            )
+            .body(())
+    })
+    .await?;
+
+    let json = res.json::<Value>().await?;
+    let ret = json["issueLinkTypes"].as_array().map(|types| {
+        types
+            .iter()
+            .filter_map(|v| v.as_object().map(as_issue_link_type))
+            .collect::<Vec<JiraIssueLinkType>>()
+    });
+
+    Ok(ret.unwrap_or_default())
+}
@@ -0,0 +1,173 @@
+use std::env;
+
+use isahc::{AsyncReadResponseExt, Request};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{error::ApiError, jira_client};
+
+const AUTHORIZE_URL: &str = "https://auth.atlassian.com/authorize";
+const TOKEN_URL: &str = "https://auth.atlassian.com/oauth/token";
+const RESOURCES_URL: &str = "https://api.atlassian.com/oauth/token/accessible-resources";
+/// Agile API requires granular scopes of Jira Software for boards and sprints
+const SCOPES: &str = "read:jira-work write:jira-work read:jira-user offline_access \
+                      read:board-scope:jira-software read:sprint:jira-software read:project:jira";
+
+/// name of cookie to keep refresh token
+pub const REFRESH_TOKEN_COOKIE: &str = "jira_refresh_token";
+/// lifetime of refresh token cookie. Atlassian expires refresh tokens unused for 90 days
+const REFRESH_TOKEN_MAX_AGE: u64 = 90 * 24 * 60 * 60;
+
+/// Configuration of OAuth 2.0 (3LO) app. Endpoints can be replaced to use other than Atlassian
+#[derive(Clone, Debug)]
+pub struct OAuthConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    pub authorize_url: Url,
+    pub token_url: String,
+    pub resources_url: String,
+}
+
+/// Token given from token endpoint
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthToken {
+    pub access_token: String,
+    /// refresh token is kept in HttpOnly cookie, and never sent to browser as JSON
+    #[serde(skip_serializing)]
+    pub refresh_token: Option<String>,
+    pub expires_in: u64,
+}
+
+/// Jira site that the token can access
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraCloudResource {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+}
+
+/// make `Set-Cookie` value to keep refresh token in browser without exposing it to scripts.
+/// `SameSite=None` is required because the API is called from other site with credentials
+pub fn refresh_token_cookie(refresh_token: &str) -> String {
+    format!(
+        "{}={}; Max-Age={}; Path=/; HttpOnly; Secure; SameSite=None",
+        REFRESH_TOKEN_COOKIE, refresh_token, REFRESH_TOKEN_MAX_AGE
+    )
+}
+
+/// get refresh token from value of `Cookie` header
+pub fn refresh_token_from_cookie(cookie: &str) -> Option<String> {
+    cookie
+        .split(';')
+        .filter_map(|v| v.trim().split_once('='))
+        .find(|(name, _)| *name == REFRESH_TOKEN_COOKIE)
+        .map(|(_, value)| value.to_string())
+        .filter(|v| !v.is_empty())
+}
+
+impl OAuthConfig {
+    /// load configuration from environment variables. Return error if the app is not configured
+    /// or the authorize URL is invalid
+    pub fn from_env() -> Result<OAuthConfig, ApiError> {
+        let required = |name: &str| {
+            env::var(name).map_err(|_| {
+                ApiError::NotConfigured(String::from("OAuth 2.0 app is not configured"))
+            })
+        };
+        let authorize_url =
+            env::var("JIRA_OAUTH_AUTHORIZE_URL").unwrap_or(String::from(AUTHORIZE_URL));
+
+        Ok(OAuthConfig {
+            client_id: required("JIRA_OAUTH_CLIENT_ID")?,
+            client_secret: required("JIRA_OAUTH_CLIENT_SECRET")?,
+            redirect_uri: required("JIRA_OAUTH_REDIRECT_URI")?,
+            authorize_url: Url::parse(&authorize_url).map_err(|e| {
+                ApiError::NotConfigured(format!("invalid JIRA_OAUTH_AUTHORIZE_URL: {}", e))
+            })?,
+            token_url: env::var("JIRA_OAUTH_TOKEN_URL").unwrap_or(String::from(TOKEN_URL)),
+            resources_url: env::var("JIRA_OAUTH_RESOURCES_URL")
+                .unwrap_or(String::from(RESOURCES_URL)),
+        })
+    }
+
+    /// get URL to redirect user to authorize this app
+    pub fn get_authorize_url(&self, state: &str) -> String {
+        let mut url = self.authorize_url.clone();
+        url.query_pairs_mut()
+            .append_pair("audience", "api.atlassian.com")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("scope", SCOPES)
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("state", state)
+            .append_pair("response_type", "code")
+            .append_pair("prompt", "consent");
+
+        url.to_string()
+    }
+
+    // request token to token endpoint
+    async fn request_token(&self, body: Value) -> Result<OAuthToken, ApiError> {
+        let mut res = jira_client::send_non_idempotent(|| {
+            Request::post(&self.token_url)
+                .header("content-type", "application/json")
+                .body(body.to_string())
+        })
+        .await?;
+
+        let json: Value = res.json().await?;
+
+        Ok(OAuthToken {
+            access_token: json["access_token"]
+                .as_str()
+                .ok_or(ApiError::InvalidResponse(String::from(
+                    "access token is not found",
+                )))?
+                .to_string(),
+            refresh_token: json["refresh_token"].as_str().map(|v| v.into()),
+            expires_in: json["expires_in"].as_u64().unwrap_or_default(),
+        })
+    }
+
+    /// exchange authorization code given to callback with token
+    pub async fn exchange_code(&self, code: &str) -> Result<OAuthToken, ApiError> {
+        self.request_token(json!({
+            "grant_type": "authorization_code",
+            "client_id": self.client_id,
+            "client_secret": self.client_secret,
+            "code": code,
+            "redirect_uri": self.redirect_uri,
+        }))
+        .await
+    }
+
+    /// get new token with refresh token
+    pub async fn refresh_token(&self, refresh_token: &str) -> Result<OAuthToken, ApiError> {
+        self.request_token(json!({
+            "grant_type": "refresh_token",
+            "client_id": self.client_id,
+            "client_secret": self.client_secret,
+            "refresh_token": refresh_token,
+        }))
+        .await
+    }
+
+    /// get Jira sites that can be accessed with the token
+    pub async fn get_accessible_resources(
+        &self,
+        access_token: &str,
+    ) -> Result<Vec<JiraCloudResource>, ApiError> {
+        let mut res = jira_client::send(|| {
+            Request::get(&self.resources_url)
+                .header("authorization", format!("Bearer {}", access_token))
+                .header("accept", "application/json")
+                .body(())
+        })
+        .await?;
+
+        Ok(res.json().await?)
+    }
+}
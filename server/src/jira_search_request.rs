@@ -1,66 +1,297 @@
-use isahc::{ReadResponseExt, Request, RequestExt, Response};
-
-use lambda_http::Body;
+use base64::{engine::general_purpose, Engine as _};
+use isahc::{AsyncReadResponseExt, Request};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
 use crate::{
-    api_type::IssueSearchRequest,
-    error::{bad_request, internal_server_error},
-    issue::{as_issue, JiraIssue},
-    jira_url::JiraUrl,
+    api_type::{IssueSearchRequest, IssueSearchResponse},
+    error::ApiError,
+    issue::{as_issue, issue_fields},
+    jira_client,
+    jira_issue_request::LoadingConfig,
+    jira_url::{JiraUrl, SearchApi},
 };
 
-// load issue with request
-pub fn search_issues(
-    request: &IssueSearchRequest,
-    url: impl JiraUrl,
-) -> Result<Vec<JiraIssue>, Response<Body>> {
-    let jql = request.jql.clone();
-
-    // load all issue keys in jql
-    let jira_url = url.get_url("/rest/api/3/search");
-    let body = json!({
-        "jql": jql,
-        "startAt": (request.page - 1).max(0) * 50,
-        "maxResults": 50,
-        "fields": vec!["status", "issuetype", "issuelinks", "subtasks", "summary"]
-    });
-
-    let cl = match Request::post(jira_url)
-        .header(
-            "authorization",
-            url.get_base_headers()
-                .get("authorization")
-                .unwrap_or(&String::from("")),
-        )
-        .header("content-type", "application/json")
-        .body(body.to_string())
-    {
-        Ok(cl) => cl,
-        Err(_) => return Err(bad_request()),
-    };
+/// maximum number of issues Jira returns in a page
+pub const MAX_PAGE_SIZE: u32 = 100;
+const DEFAULT_PAGE_SIZE: u32 = 50;
+/// maximum page number that can be requested without cursor in `search/jql`
+const MAX_PAGE_WITHOUT_CURSOR: u32 = 20;
 
-    match cl.send() {
-        Ok(mut res) => {
-            let v = res.json::<Value>();
+/// position of a page to search
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PageCursor {
+    /// offset of the first issue. For `search`
+    Offset(usize),
+    /// token given from the previous page. None is the first page. For `search/jql`
+    Token(Option<String>),
+}
 
-            match v {
-                Err(_) => Ok(Vec::new()),
-                Ok(got_issues) => {
-                    let ret = got_issues["issues"]
-                        .as_array()
-                        .map(|issues| issues.iter().map(as_issue).collect::<Vec<JiraIssue>>());
+/// a page of issues matched with JQL
+#[derive(Clone, Debug, Default)]
+pub struct SearchPage {
+    pub issues: Vec<Value>,
+    /// number of all issues matched. Only `search` returns it
+    pub total: Option<usize>,
+    /// token of the next page. None if this page is the last one
+    pub next_page_token: Option<String>,
+}
 
-                    Ok(ret.unwrap_or(Vec::new()))
-                }
+/// send request of search API
+async fn post_search(path: &str, body: Value, url: &impl JiraUrl) -> Result<Value, ApiError> {
+    let jira_url = url.get_api_url(path);
+
+    let mut res = jira_client::send(|| {
+        Request::post(&jira_url)
+            .header(
+                "authorization",
+                url.get_base_headers()
+                    .get("authorization")
+                    .unwrap_or(&String::from("")),
+            )
+            .header("content-type", "application/json")
+            .body(body.to_string())
+    })
+    .await?;
+
+    Ok(res.json::<Value>().await?)
+}
+
+/// search a page of issues with JQL. API is selected by the type of cursor
+pub async fn search_page(
+    jql: &str,
+    cursor: &PageCursor,
+    page_size: usize,
+    fields: &[&str],
+    url: &impl JiraUrl,
+) -> Result<SearchPage, ApiError> {
+    match cursor {
+        PageCursor::Offset(start_at) => {
+            let json = post_search(
+                "search",
+                json!({
+                    "jql": jql,
+                    "startAt": start_at,
+                    "maxResults": page_size,
+                    "fields": fields
+                }),
+                url,
+            )
+            .await?;
+            let issues = json["issues"].as_array().cloned().unwrap_or_default();
+            let total = json["total"].as_u64().unwrap_or_default() as usize;
+
+            Ok(SearchPage {
+                next_page_token: None,
+                total: Some(total),
+                issues,
+            })
+        }
+        PageCursor::Token(token) => {
+            let mut body = json!({
+                "jql": jql,
+                "maxResults": page_size,
+                "fields": fields
+            });
+            if let Some(token) = token {
+                body["nextPageToken"] = json!(token);
             }
+
+            let json = post_search("search/jql", body, url).await?;
+            let is_last = json["isLast"].as_bool().unwrap_or_default();
+
+            Ok(SearchPage {
+                issues: json["issues"].as_array().cloned().unwrap_or_default(),
+                total: None,
+                next_page_token: json["nextPageToken"]
+                    .as_str()
+                    .filter(|_| !is_last)
+                    .map(|v| v.to_string()),
+            })
+        }
+    }
+}
+
+/// get approximate number of issues matched with JQL. `search/jql` does not return it
+pub async fn approximate_count(jql: &str, url: &impl JiraUrl) -> Result<usize, ApiError> {
+    let json = post_search("search/approximate-count", json!({ "jql": jql }), url).await?;
+
+    Ok(json["count"].as_u64().unwrap_or_default() as usize)
+}
+
+/// cursor of page given to client. It is encoded to be opaque for client. JQL and page size are
+/// kept to reject the cursor given with another search
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct SearchCursor {
+    jql: String,
+    page_size: u32,
+    page: u32,
+    position: PageCursor,
+}
+
+impl SearchCursor {
+    fn encode(&self) -> String {
+        let json = serde_json::to_string(self).expect("should be serializable");
+        general_purpose::URL_SAFE_NO_PAD.encode(json)
+    }
+
+    fn decode(value: &str) -> Result<SearchCursor, ApiError> {
+        general_purpose::URL_SAFE_NO_PAD
+            .decode(value)
+            .ok()
+            .and_then(|v| serde_json::from_slice(&v).ok())
+            .ok_or(ApiError::InvalidRequest(String::from("invalid cursor")))
+    }
+
+    /// decode cursor given with the search, and check that it is made by the same search
+    fn decode_for(value: &str, jql: &str, page_size: u32) -> Result<SearchCursor, ApiError> {
+        let cursor = SearchCursor::decode(value)?;
+
+        if cursor.jql != jql || cursor.page_size != page_size {
+            return Err(ApiError::InvalidRequest(String::from(
+                "cursor does not match the search",
+            )));
         }
-        Err(e) => {
-            if e.is_client() {
-                Err(bad_request())
-            } else {
-                Err(internal_server_error())
+
+        Ok(cursor)
+    }
+}
+
+/// get the cursor of the requested page. Token-based paging can not jump to the page, so follow
+/// tokens from the first page with only ids of issues. Pages after `MAX_PAGE_WITHOUT_CURSOR` must
+/// be requested with cursor
+async fn cursor_of_page(
+    jql: &str,
+    page: u32,
+    page_size: usize,
+    url: &impl JiraUrl,
+) -> Result<Option<PageCursor>, ApiError> {
+    match url.search_api() {
+        SearchApi::Offset => Ok(Some(PageCursor::Offset((page - 1) as usize * page_size))),
+        SearchApi::Jql => {
+            if page > MAX_PAGE_WITHOUT_CURSOR {
+                return Err(ApiError::InvalidRequest(format!(
+                    "page after {} must be requested with cursor",
+                    MAX_PAGE_WITHOUT_CURSOR
+                )));
+            }
+
+            let mut cursor = PageCursor::Token(None);
+            for _ in 1..page {
+                match search_page(jql, &cursor, page_size, &["id"], url)
+                    .await?
+                    .next_page_token
+                {
+                    Some(token) => cursor = PageCursor::Token(Some(token)),
+                    None => return Ok(None),
+                }
             }
+
+            Ok(Some(cursor))
         }
     }
 }
+
+// search issues with request
+pub async fn search_issues(
+    request: &IssueSearchRequest,
+    url: impl JiraUrl,
+) -> Result<IssueSearchResponse, ApiError> {
+    let page_size = request
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let config = LoadingConfig::global();
+    let fields = issue_fields(&config.story_points_field);
+    let requested = match &request.cursor {
+        Some(cursor) => SearchCursor::decode_for(cursor, &request.jql, page_size)?,
+        None => {
+            let page = request.page.max(1);
+            let position = cursor_of_page(&request.jql, page, page_size as usize, &url).await?;
+
+            match position {
+                Some(position) => SearchCursor {
+                    jql: request.jql.clone(),
+                    page_size,
+                    page,
+                    position,
+                },
+                // the page is beyond the last page
+                None => {
+                    return Ok(IssueSearchResponse {
+                        issues: Vec::new(),
+                        total: None,
+                        page,
+                        page_size,
+                        has_next: false,
+                        cursor: None,
+                    })
+                }
+            }
+        }
+    };
+
+    // search/jql does not return total, so count it at the same time
+    let (result, count) = futures::join!(
+        search_page(
+            &request.jql,
+            &requested.position,
+            page_size as usize,
+            &fields,
+            &url
+        ),
+        async {
+            match requested.position {
+                PageCursor::Token(_) => approximate_count(&request.jql, &url).await.ok(),
+                PageCursor::Offset(_) => None,
+            }
+        }
+    );
+    let result = result?;
+
+    let next = match (&requested.position, result.next_page_token) {
+        (PageCursor::Offset(start_at), _) => {
+            let next = start_at + result.issues.len();
+            (!result.issues.is_empty() && next < result.total.unwrap_or_default())
+                .then_some(PageCursor::Offset(next))
+        }
+        (PageCursor::Token(_), token) => token.map(|v| PageCursor::Token(Some(v))),
+    };
+    let cursor = match next {
+        Some(position) => Some(
+            SearchCursor {
+                jql: request.jql.clone(),
+                page_size,
+                page: requested
+                    .page
+                    .checked_add(1)
+                    .ok_or(ApiError::InvalidRequest(String::from("invalid cursor")))?,
+                position,
+            }
+            .encode(),
+        ),
+        None => None,
+    };
+
+    Ok(IssueSearchResponse {
+        // skip malformed issues as same as loading issues
+        issues: result
+            .issues
+            .iter()
+            .filter_map(|v| match as_issue(v, &config.story_points_field) {
+                Ok(issue) => Some(issue),
+                Err(e) => {
+                    tracing::warn!("skip malformed issue in search result: {}", e);
+                    None
+                }
+            })
+            .collect(),
+        total: result.total.or(count),
+        page: requested.page,
+        page_size,
+        has_next: cursor.is_some(),
+        cursor,
+    })
+}
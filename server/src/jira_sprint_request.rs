@@ -0,0 +1,252 @@
+use isahc::{AsyncReadResponseExt, Request};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+    api_type::{BoardListRequest, BoardListResponse, SprintListRequest, SprintListResponse},
+    error::ApiError,
+    jira_client,
+    jira_url::JiraUrl,
+};
+
+/// maximum number of values Agile API returns in a page
+const MAX_PAGE_SIZE: u32 = 50;
+
+/// board of Jira Software
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraBoard {
+    pub id: u64,
+    pub name: String,
+    /// `scrum` or `kanban`. Only scrum boards have sprints
+    pub board_type: String,
+    pub project_key: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SprintState {
+    Future,
+    Active,
+    Closed,
+}
+
+impl SprintState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SprintState::Future => "future",
+            SprintState::Active => "active",
+            SprintState::Closed => "closed",
+        }
+    }
+}
+
+/// sprint of a board
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JiraSprint {
+    pub id: u64,
+    pub name: String,
+    pub state: SprintState,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub complete_date: Option<String>,
+    pub origin_board_id: Option<u64>,
+    pub goal: Option<String>,
+}
+
+/// json to board
+fn as_board(v: &Value) -> Option<JiraBoard> {
+    Some(JiraBoard {
+        id: v["id"].as_u64()?,
+        name: v["name"].as_str().map(|v| v.into()).unwrap_or_default(),
+        board_type: v["type"].as_str().map(|v| v.into()).unwrap_or_default(),
+        project_key: v["location"]["projectKey"].as_str().map(|v| v.into()),
+    })
+}
+
+/// a page of values returned from Agile API
+struct AgilePage {
+    values: Vec<Value>,
+    has_next: bool,
+}
+
+/// get a page of values from Agile API. Page number starts from 1. Offset is calculated in u64
+/// because page is given from client
+async fn get_agile_page(
+    path: &str,
+    params: &[(&str, String)],
+    page: u32,
+    page_size: u32,
+    url: &impl JiraUrl,
+) -> Result<AgilePage, ApiError> {
+    let start_at = u64::from(page.max(1) - 1) * u64::from(page_size);
+    let mut jira_url = Url::parse(&url.get_agile_url(path))
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+    jira_url
+        .query_pairs_mut()
+        .extend_pairs(params)
+        .append_pair("startAt", &start_at.to_string())
+        .append_pair("maxResults", &page_size.to_string());
+
+    let mut res = jira_client::send(|| {
+        Request::get(jira_url.as_str())
+            .header(
+                "authorization",
+                url.get_base_headers()
+                    .get("authorization")
+                    .unwrap_or(&String::from("")),
+            )
+            .body(())
+    })
+    .await?;
+
+    let json = res.json::<Value>().await?;
+    let values = json["values"].as_array().cloned().unwrap_or_default();
+    let has_next = match json["isLast"].as_bool() {
+        Some(is_last) => !is_last,
+        // some versions of Jira do not return isLast
+        None => json["total"]
+            .as_u64()
+            .map(|total| start_at + (values.len() as u64) < total)
+            .unwrap_or_default(),
+    };
+
+    Ok(AgilePage { values, has_next })
+}
+
+// get boards with request
+pub async fn get_boards(
+    request: &BoardListRequest,
+    url: impl JiraUrl,
+) -> Result<BoardListResponse, ApiError> {
+    let page = request.page.max(1);
+    let page_size = request
+        .page_size
+        .unwrap_or(MAX_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let mut params = Vec::new();
+    if let Some(name) = &request.name {
+        params.push(("name", name.clone()));
+    }
+    if let Some(project) = &request.project {
+        params.push(("projectKeyOrId", project.clone()));
+    }
+
+    let result = get_agile_page("board", &params, page, page_size, &url).await?;
+
+    Ok(BoardListResponse {
+        boards: result.values.iter().filter_map(as_board).collect(),
+        page,
+        page_size,
+        has_next: result.has_next,
+    })
+}
+
+// get sprints of a board with request
+pub async fn get_sprints(
+    request: &SprintListRequest,
+    url: impl JiraUrl,
+) -> Result<SprintListResponse, ApiError> {
+    let page = request.page.max(1);
+    let page_size = request
+        .page_size
+        .unwrap_or(MAX_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let mut params = Vec::new();
+    if !request.states.is_empty() {
+        let states = request
+            .states
+            .iter()
+            .map(|v| v.as_str())
+            .collect::<Vec<&str>>()
+            .join(",");
+        params.push(("state", states));
+    }
+
+    let result = get_agile_page(
+        &format!("board/{}/sprint", request.board_id),
+        &params,
+        page,
+        page_size,
+        &url,
+    )
+    .await?;
+
+    Ok(SprintListResponse {
+        sprints: result
+            .values
+            .into_iter()
+            .filter_map(|v| serde_json::from_value(v).ok())
+            .collect(),
+        page,
+        page_size,
+        has_next: result.has_next,
+    })
+}
+
+/// remove highlight tags Jira adds to suggestions
+fn strip_tags(value: &str) -> String {
+    Regex::new("<[^>]*>")
+        .expect("should be valid pattern")
+        .replace_all(value, "")
+        .trim()
+        .to_string()
+}
+
+/// true if the suggested name is the sprint. Jira suggests sprints as `name (board)`
+fn is_sprint_of(display_name: &str, name: &str) -> bool {
+    let display_name = strip_tags(display_name);
+
+    display_name == name || display_name.starts_with(&format!("{} (", name))
+}
+
+/// resolve name of sprint to ids. Sprints in different boards can have the same name, so all
+/// matched ids are returned. Numeric value is treated as id of sprint
+pub async fn resolve_sprint_ids(sprint: &str, url: &impl JiraUrl) -> Result<Vec<u64>, ApiError> {
+    let sprint = sprint.trim();
+    if let Ok(id) = sprint.parse::<u64>() {
+        return Ok(vec![id]);
+    }
+
+    let jira_url = Url::parse_with_params(
+        &url.get_api_url("jql/autocompletedata/suggestions"),
+        &[("fieldName", "sprint"), ("fieldValue", sprint)],
+    )
+    .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+
+    let mut res = jira_client::send(|| {
+        Request::get(jira_url.as_str())
+            .header(
+                "authorization",
+                url.get_base_headers()
+                    .get("authorization")
+                    .unwrap_or(&String::from("")),
+            )
+            .body(())
+    })
+    .await?;
+
+    let json = res.json::<Value>().await?;
+    let ids = json["results"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .filter(|v| is_sprint_of(v["displayName"].as_str().unwrap_or_default(), sprint))
+                .filter_map(|v| v["value"].as_str().and_then(|v| v.parse::<u64>().ok()))
+                .collect::<Vec<u64>>()
+        })
+        .unwrap_or_default();
+
+    if ids.is_empty() {
+        return Err(ApiError::InvalidRequest(format!(
+            "unknown sprint: {}",
+            sprint
+        )));
+    }
+
+    Ok(ids)
+}
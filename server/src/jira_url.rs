@@ -1,13 +1,118 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, env, sync::OnceLock};
 
 use base64::{engine::general_purpose, Engine as _};
 use serde::Deserialize;
+use url::Url;
+
+use crate::error::ApiError;
+
+/// API to search issues with JQL
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchApi {
+    /// `search/jql` with token-based paging. Jira Cloud only
+    #[default]
+    Jql,
+    /// `search` with offset paging. It is deprecated in Jira Cloud
+    Offset,
+}
 
 #[derive(Deserialize, Clone, Debug)]
 pub struct JiraAuhtorization {
     pub jira_token: String,
     pub email: String,
     pub user_domain: String,
+    #[serde(default)]
+    pub search_api: SearchApi,
+}
+
+/// Credential for Jira Data Center/Server with Personal Access Token
+#[derive(Deserialize, Clone, Debug)]
+pub struct JiraServerAuthorization {
+    pub base_url: String,
+    pub token: String,
+}
+
+/// Base URLs of Jira Data Center/Server that users can connect to. Other URLs are rejected to
+/// avoid sending credentials to internal hosts. Only https is allowed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ServerBaseUrls(Vec<String>);
+
+static SERVER_BASE_URLS: OnceLock<ServerBaseUrls> = OnceLock::new();
+
+/// normalize base URL to compare. Return None if it is not https or has parts other than host
+/// and path
+fn normalize_base_url(value: &str) -> Option<String> {
+    let url = Url::parse(value.trim()).ok()?;
+
+    if url.scheme() != "https"
+        || !url.username().is_empty()
+        || url.password().is_some()
+        || url.query().is_some()
+        || url.fragment().is_some()
+    {
+        return None;
+    }
+
+    Some(format!(
+        "https://{}:{}{}",
+        url.host_str()?,
+        url.port_or_known_default()?,
+        url.path().trim_end_matches('/')
+    ))
+}
+
+impl ServerBaseUrls {
+    /// make allow-list from base URLs. URLs other than https are ignored
+    pub fn new<T: AsRef<str>>(base_urls: &[T]) -> ServerBaseUrls {
+        ServerBaseUrls(
+            base_urls
+                .iter()
+                .filter_map(|v| normalize_base_url(v.as_ref()))
+                .collect(),
+        )
+    }
+
+    /// load allow-list from comma-separated `JIRA_SERVER_BASE_URLS` environment variable.
+    /// No Data Center/Server is allowed if it is not given
+    pub fn from_env() -> ServerBaseUrls {
+        let base_urls = env::var("JIRA_SERVER_BASE_URLS").unwrap_or_default();
+
+        ServerBaseUrls::new(&base_urls.split(',').collect::<Vec<&str>>())
+    }
+
+    /// get allow-list loaded at the first call
+    pub fn global() -> &'static ServerBaseUrls {
+        SERVER_BASE_URLS.get_or_init(ServerBaseUrls::from_env)
+    }
+
+    /// check that base URL given from user is allowed
+    pub fn check(&self, base_url: &str) -> Result<(), ApiError> {
+        match normalize_base_url(base_url) {
+            Some(v) if self.0.contains(&v) => Ok(()),
+            _ => Err(ApiError::InvalidRequest(format!(
+                "base URL is not allowed: {}",
+                base_url
+            ))),
+        }
+    }
+}
+
+/// Credential for Jira Cloud with access token given by OAuth 2.0 (3LO)
+#[derive(Deserialize, Clone, Debug)]
+pub struct JiraOAuthAuthorization {
+    pub cloud_id: String,
+    pub access_token: String,
+    #[serde(default)]
+    pub search_api: SearchApi,
+}
+
+/// Credential for any Jira instance
+#[derive(Clone, Debug)]
+pub enum JiraCredential {
+    Cloud(JiraAuhtorization),
+    Server(JiraServerAuthorization),
+    OAuth(JiraOAuthAuthorization),
 }
 
 pub trait JiraUrl {
@@ -15,6 +120,30 @@ pub trait JiraUrl {
     fn get_url(&self, path: &str) -> String;
 
     fn get_base_headers(&self) -> HashMap<String, String>;
+
+    // version of REST API. Jira Cloud uses 3, and Data Center/Server uses 2
+    fn api_version(&self) -> u8 {
+        3
+    }
+
+    // get full url of REST API with path under `/rest/api/{version}`
+    fn get_api_url(&self, path: &str) -> String {
+        let path = path.trim_start_matches(' ');
+        let path = path.trim_start_matches('/');
+        self.get_url(&format!("/rest/api/{}/{}", self.api_version(), path))
+    }
+
+    // get full url of Jira Software REST API with path under `/rest/agile/1.0`
+    fn get_agile_url(&self, path: &str) -> String {
+        let path = path.trim_start_matches(' ');
+        let path = path.trim_start_matches('/');
+        self.get_url(&format!("/rest/agile/1.0/{}", path))
+    }
+
+    // API to search issues. Offset paging is supported by all Jira
+    fn search_api(&self) -> SearchApi {
+        SearchApi::Offset
+    }
 }
 
 impl JiraUrl for JiraAuhtorization {
@@ -35,12 +164,104 @@ impl JiraUrl for JiraAuhtorization {
 
         map
     }
+
+    fn search_api(&self) -> SearchApi {
+        self.search_api
+    }
+}
+
+impl JiraUrl for JiraServerAuthorization {
+    fn get_url(&self, path: &str) -> String {
+        let path = path.trim_start_matches(' ');
+        let path = path.trim_start_matches('/');
+        format!("{}/{}", self.base_url.trim_end_matches('/'), path)
+    }
+
+    fn get_base_headers(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+
+        map.insert(
+            String::from("authorization"),
+            format!("Bearer {}", self.token),
+        );
+
+        map
+    }
+
+    fn api_version(&self) -> u8 {
+        2
+    }
+}
+
+impl JiraUrl for JiraOAuthAuthorization {
+    fn get_url(&self, path: &str) -> String {
+        let path = path.trim_start_matches(' ');
+        let path = path.trim_start_matches('/');
+        format!(
+            "https://api.atlassian.com/ex/jira/{}/{}",
+            self.cloud_id, path
+        )
+    }
+
+    fn get_base_headers(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+
+        map.insert(
+            String::from("authorization"),
+            format!("Bearer {}", self.access_token),
+        );
+
+        map
+    }
+
+    fn search_api(&self) -> SearchApi {
+        self.search_api
+    }
+}
+
+impl JiraUrl for JiraCredential {
+    fn get_url(&self, path: &str) -> String {
+        match self {
+            JiraCredential::Cloud(v) => v.get_url(path),
+            JiraCredential::Server(v) => v.get_url(path),
+            JiraCredential::OAuth(v) => v.get_url(path),
+        }
+    }
+
+    fn get_base_headers(&self) -> HashMap<String, String> {
+        match self {
+            JiraCredential::Cloud(v) => v.get_base_headers(),
+            JiraCredential::Server(v) => v.get_base_headers(),
+            JiraCredential::OAuth(v) => v.get_base_headers(),
+        }
+    }
+
+    fn api_version(&self) -> u8 {
+        match self {
+            JiraCredential::Cloud(v) => v.api_version(),
+            JiraCredential::Server(v) => v.api_version(),
+            JiraCredential::OAuth(v) => v.api_version(),
+        }
+    }
+
+    fn search_api(&self) -> SearchApi {
+        match self {
+            JiraCredential::Cloud(v) => v.search_api(),
+            JiraCredential::Server(v) => v.search_api(),
+            JiraCredential::OAuth(v) => v.search_api(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::JiraAuhtorization;
+    use super::JiraOAuthAuthorization;
+    use super::JiraServerAuthorization;
     use super::JiraUrl;
+    use super::SearchApi;
+    use super::ServerBaseUrls;
+    use crate::error::ApiError;
 
     #[test]
     fn get_url_for_jira_cloud() {
@@ -49,6 +270,7 @@ mod tests {
             jira_token: String::from("token"),
             email: String::from("test@example.com"),
             user_domain: String::from("domain"),
+            search_api: SearchApi::default(),
         };
 
         // do
@@ -65,6 +287,7 @@ mod tests {
             jira_token: String::from("token"),
             email: String::from("test@example.com"),
             user_domain: String::from("domain"),
+            search_api: SearchApi::default(),
         };
 
         // do
@@ -86,6 +309,7 @@ mod tests {
             jira_token: String::from("token"),
             email: String::from("test@example.com"),
             user_domain: String::from("domain"),
+            search_api: SearchApi::default(),
         };
 
         // do
@@ -99,4 +323,125 @@ mod tests {
             "Basic dGVzdEBleGFtcGxlLmNvbTp0b2tlbg=="
         );
     }
+
+    #[test]
+    fn get_api_url_for_jira_cloud() {
+        // arrange
+        let auth = JiraAuhtorization {
+            jira_token: String::from("token"),
+            email: String::from("test@example.com"),
+            user_domain: String::from("domain"),
+            search_api: SearchApi::default(),
+        };
+
+        // do
+        let url = auth.get_api_url("/search");
+
+        // verify
+        assert_eq!(url, "https://domain.atlassian.net/rest/api/3/search");
+    }
+
+    #[test]
+    fn get_api_url_for_jira_server() {
+        // arrange
+        let auth = JiraServerAuthorization {
+            base_url: String::from("https://jira.example.com/jira/"),
+            token: String::from("token"),
+        };
+
+        // do
+        let url = auth.get_api_url("search");
+
+        // verify
+        assert_eq!(url, "https://jira.example.com/jira/rest/api/2/search");
+        assert_eq!(auth.search_api(), SearchApi::Offset);
+    }
+
+    #[test]
+    fn get_agile_url_for_jira_server() {
+        // arrange
+        let auth = JiraServerAuthorization {
+            base_url: String::from("https://jira.example.com/jira/"),
+            token: String::from("token"),
+        };
+
+        // do
+        let url = auth.get_agile_url("/board/1/sprint");
+
+        // verify
+        assert_eq!(
+            url,
+            "https://jira.example.com/jira/rest/agile/1.0/board/1/sprint"
+        );
+    }
+
+    #[test]
+    fn get_bearer_authorization_header_for_jira_server() {
+        // arrange
+        let auth = JiraServerAuthorization {
+            base_url: String::from("https://jira.example.com"),
+            token: String::from("token"),
+        };
+
+        // do
+        let headers = auth.get_base_headers();
+
+        // verify
+        assert_eq!(
+            headers
+                .get("authorization")
+                .expect("can not found authorization"),
+            "Bearer token"
+        );
+    }
+
+    #[test]
+    fn get_api_url_for_oauth() {
+        // arrange
+        let auth = JiraOAuthAuthorization {
+            cloud_id: String::from("cloud"),
+            access_token: String::from("token"),
+            search_api: SearchApi::default(),
+        };
+
+        // do
+        let url = auth.get_api_url("search");
+        let headers = auth.get_base_headers();
+
+        // verify
+        assert_eq!(
+            url,
+            "https://api.atlassian.com/ex/jira/cloud/rest/api/3/search"
+        );
+        assert_eq!(
+            headers
+                .get("authorization")
+                .expect("can not found authorization"),
+            "Bearer token"
+        );
+    }
+
+    #[test]
+    fn allow_only_listed_https_base_url() {
+        // arrange
+        let allowed =
+            ServerBaseUrls::new(&["https://jira.example.com/jira/", "http://plain.example.com"]);
+
+        // do
+        let listed = allowed.check("https://JIRA.example.com:443/jira");
+        let other_path = allowed.check("https://jira.example.com/other");
+        let metadata = allowed.check("http://169.254.169.254/latest");
+        let plain = allowed.check("http://plain.example.com");
+
+        // verify
+        assert_eq!(listed, Ok(()));
+        assert!(other_path.is_err());
+        assert_eq!(
+            metadata,
+            Err(ApiError::InvalidRequest(
+                "base URL is not allowed: http://169.254.169.254/latest".to_string()
+            ))
+        );
+        assert!(plain.is_err());
+    }
 }
@@ -0,0 +1,327 @@
+use std::{env, fmt::Display, sync::OnceLock};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::error::ApiError;
+
+/// default pattern of issue key. Project key and number such as `PROJ-123`
+pub const DEFAULT_ISSUE_KEY_PATTERN: &str = r"(?i)^[A-Z][A-Z0-9_]*-[0-9]+$";
+
+/// Key of an issue that is safe to put into JQL
+#[derive(Serialize, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct IssueKey(String);
+
+/// pattern to validate issue keys given from users
+#[derive(Clone, Debug)]
+pub struct IssueKeyPattern(Regex);
+
+impl Default for IssueKeyPattern {
+    fn default() -> Self {
+        IssueKeyPattern(Regex::new(DEFAULT_ISSUE_KEY_PATTERN).expect("should be valid pattern"))
+    }
+}
+
+impl IssueKeyPattern {
+    /// make pattern from regular expression
+    pub fn new(pattern: &str) -> Result<IssueKeyPattern, regex::Error> {
+        Regex::new(pattern).map(IssueKeyPattern)
+    }
+
+    /// load pattern from `JIRA_ISSUE_KEY_PATTERN` environment variable for custom projects.
+    /// Use default pattern if it is not given or invalid
+    pub fn from_env() -> IssueKeyPattern {
+        match env::var("JIRA_ISSUE_KEY_PATTERN") {
+            Ok(pattern) => IssueKeyPattern::new(&pattern).unwrap_or_else(|e| {
+                tracing::warn!("invalid JIRA_ISSUE_KEY_PATTERN, use default pattern: {}", e);
+                IssueKeyPattern::default()
+            }),
+            Err(_) => IssueKeyPattern::default(),
+        }
+    }
+}
+
+impl IssueKey {
+    /// validate key given from user. Key is uppercased because Jira returns keys in uppercase
+    /// even if they are requested in lowercase
+    pub fn parse(value: &str, pattern: &IssueKeyPattern) -> Result<IssueKey, ApiError> {
+        let value = value.trim();
+
+        if pattern.0.is_match(value) {
+            Ok(IssueKey(value.to_uppercase()))
+        } else {
+            Err(ApiError::InvalidRequest(format!(
+                "invalid issue key: {}",
+                value
+            )))
+        }
+    }
+
+    /// validate all keys given from user
+    pub fn parse_all(
+        values: &[String],
+        pattern: &IssueKeyPattern,
+    ) -> Result<Vec<IssueKey>, ApiError> {
+        values.iter().map(|v| IssueKey::parse(v, pattern)).collect()
+    }
+
+    /// key returned from Jira. It is not validated because it is not given from users
+    pub(crate) fn from_jira(value: &str) -> IssueKey {
+        IssueKey(value.to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for IssueKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for IssueKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// quote value as string literal of JQL
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Builder of JQL. Values except numeric ids are quoted, and clauses are joined with AND
+#[derive(Clone, Debug, Default)]
+pub struct Jql {
+    clauses: Vec<String>,
+}
+
+impl Jql {
+    pub fn new() -> Jql {
+        Jql::default()
+    }
+
+    /// add `field = value` clause
+    pub fn equals(mut self, field: &str, value: &str) -> Jql {
+        self.clauses.push(format!("{} = {}", field, quote(value)));
+        self
+    }
+
+    /// add `field in (values)` clause
+    pub fn in_values<T: AsRef<str>>(mut self, field: &str, values: &[T]) -> Jql {
+        let values = values
+            .iter()
+            .map(|v| quote(v.as_ref()))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        self.clauses.push(format!("{} in ({})", field, values));
+        self
+    }
+
+    /// add `field in (ids)` clause. Ids are numbers, so they are not quoted
+    pub fn in_ids(mut self, field: &str, ids: &[u64]) -> Jql {
+        let ids = ids
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        self.clauses.push(format!("{} in ({})", field, ids));
+        self
+    }
+
+    /// add `(field1 = value OR field2 = value ...)` clause
+    pub fn any_equals(mut self, fields: &[&str], value: &str) -> Jql {
+        let clauses = fields
+            .iter()
+            .map(|field| format!("{} = {}", field, quote(value)))
+            .collect::<Vec<String>>()
+            .join(" OR ");
+
+        self.clauses.push(format!("({})", clauses));
+        self
+    }
+
+    /// add `key in (keys)` clause
+    pub fn keys(self, keys: &[IssueKey]) -> Jql {
+        self.in_values("key", keys)
+    }
+
+    /// add JQL given from Jira, such as JQL of saved filter. `ORDER BY` is removed to join it
+    /// with other clauses
+    pub fn and_jql(mut self, jql: &str) -> Jql {
+        let jql = match order_by_position(jql) {
+            Some(position) => &jql[..position],
+            None => jql,
+        };
+
+        if !jql.trim().is_empty() {
+            self.clauses.push(format!("({})", jql.trim()));
+        }
+        self
+    }
+
+    /// true if no clause is added
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+}
+
+/// pattern of `ORDER BY` at the start of text
+fn order_by_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?i)^order\s+by\b").expect("should be valid pattern"))
+}
+
+/// get position of `ORDER BY` clause in JQL. Quoted strings are skipped, because they can contain
+/// the same words
+fn order_by_position(jql: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut previous: Option<char> = None;
+
+    for (i, c) in jql.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if !previous.is_some_and(|v| v.is_alphanumeric() || v == '_')
+                && order_by_pattern().is_match(&jql[i..]) =>
+            {
+                return Some(i)
+            }
+            None => (),
+        }
+        previous = Some(c);
+    }
+
+    None
+}
+
+impl Display for Jql {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.clauses.join(" AND "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IssueKey, IssueKeyPattern, Jql};
+    use crate::error::ApiError;
+
+    #[test]
+    fn validate_issue_key() {
+        // arrange
+        let pattern = IssueKeyPattern::default();
+
+        // do
+        let valid = IssueKey::parse("PROJ-123", &pattern);
+        let lowercase = IssueKey::parse("proj-123", &pattern);
+        let injected = IssueKey::parse("PROJ-1) OR project = SECRET", &pattern);
+
+        // verify
+        assert_eq!(valid.map(|v| v.to_string()), Ok("PROJ-123".to_string()));
+        assert_eq!(lowercase.map(|v| v.to_string()), Ok("PROJ-123".to_string()));
+        assert_eq!(
+            injected,
+            Err(ApiError::InvalidRequest(
+                "invalid issue key: PROJ-1) OR project = SECRET".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn validate_issue_key_with_custom_pattern() {
+        // arrange
+        let pattern = IssueKeyPattern::new(r"^[a-z]+#[0-9]+$").unwrap();
+
+        // do
+        let custom = IssueKey::parse("proj#1", &pattern);
+        let default = IssueKey::parse("PROJ-1", &pattern);
+
+        // verify
+        assert!(custom.is_ok());
+        assert!(default.is_err());
+    }
+
+    #[test]
+    fn quote_values_in_jql() {
+        // arrange
+        let keys = vec![IssueKey::from_jira("A-1"), IssueKey::from_jira("B-\"2\"")];
+
+        // do
+        let jql = Jql::new().keys(&keys).equals("sprint", "sprint \\ 1");
+
+        // verify
+        assert_eq!(
+            jql.to_string(),
+            r#"key in ("A-1", "B-\"2\"") AND sprint = "sprint \\ 1""#
+        );
+    }
+
+    #[test]
+    fn combine_fields_and_ids_in_jql() {
+        // arrange
+        let jql = Jql::new()
+            .any_equals(&["parent", "\"Epic Link\""], "EPIC-1")
+            .in_ids("sprint", &[42, 43]);
+
+        // do
+        let ret = jql.to_string();
+
+        // verify
+        assert_eq!(
+            ret,
+            r#"(parent = "EPIC-1" OR "Epic Link" = "EPIC-1") AND sprint in (42, 43)"#
+        );
+    }
+
+    #[test]
+    fn join_jql_of_filter_without_order() {
+        // arrange
+        let jql = Jql::new()
+            .in_ids("sprint", &[42])
+            .and_jql("project = PROJ OR labels = team order by Rank ASC");
+
+        // do
+        let ret = jql.to_string();
+
+        // verify
+        assert_eq!(ret, "sprint in (42) AND (project = PROJ OR labels = team)");
+    }
+
+    #[test]
+    fn keep_order_by_in_quoted_string() {
+        // arrange
+        let jql = Jql::new().and_jql(
+            r#"summary ~ "sort order by date" AND project = 'order by' ORDER BY Rank ASC"#,
+        );
+
+        // do
+        let ret = jql.to_string();
+
+        // verify
+        assert_eq!(
+            ret,
+            r#"(summary ~ "sort order by date" AND project = 'order by')"#
+        );
+    }
+
+    #[test]
+    fn keep_jql_without_order_by() {
+        // arrange
+        let jql = Jql::new().and_jql(r#"summary ~ "a \" order by" AND border = by"#);
+
+        // do
+        let ret = jql.to_string();
+
+        // verify
+        assert_eq!(ret, r#"(summary ~ "a \" order by" AND border = by)"#);
+    }
+}
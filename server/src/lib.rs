@@ -1,209 +1,330 @@
 pub mod api_type;
-mod error;
+pub mod cors;
+pub mod error;
 pub mod issue;
+pub mod issue_graph;
+pub mod jira_client;
+pub mod jira_filter_request;
 pub mod jira_issue_request;
 pub mod jira_link_request;
+pub mod jira_link_type_request;
+pub mod jira_oauth;
 pub mod jira_search_request;
+pub mod jira_sprint_request;
 pub mod jira_url;
+pub mod jql;
+pub mod router;
 
-use api_type::{CreateLinkRequest, DeleteLinkRequest, IssueLoadingRequest, IssueSearchRequest};
-use isahc::http::Method;
+use std::collections::HashMap;
+
+use api_type::{
+    BoardListRequest, CreateLinkRequest, CriticalPathRequest, CriticalPathResponse,
+    DeleteLinkRequest, IssueConditionLoadingRequest, IssueLayersResponse, IssueLoadingRequest,
+    IssueLoadingResponse, IssueSearchRequest, OAuthCallbackRequest, OAuthTokenResponse,
+    SprintListRequest,
+};
+use cors::CorsConfig;
+use error::ApiError;
+use issue_graph::IssueGraph;
+use jira_client::RetryPolicy;
 use jira_link_request::{create_link, delete_link};
 
-use jira_url::JiraAuhtorization;
-use lambda_http::{Body, Error, Request, Response};
+use jira_oauth::{refresh_token_cookie, refresh_token_from_cookie, OAuthConfig, OAuthToken};
+use jira_url::{
+    JiraAuhtorization, JiraCredential, JiraOAuthAuthorization, JiraServerAuthorization, SearchApi,
+    ServerBaseUrls,
+};
+use lambda_http::{http::HeaderValue, Body, Error, Request, Response};
+use router::Router;
 
-use serde_json::json;
+use serde::{de::DeserializeOwned, Serialize};
 
 pub async fn handler(event: Request) -> Result<Response<Body>, Error> {
-    // Extract some useful information from the request
-    let unmatch = not_found();
-
-    match event.uri().path() {
-        "/prod/get-issues" => match *event.method() {
-            Method::POST => execute_get_issues(&event).await,
-            Method::OPTIONS => preflight(&event),
-            _ => unmatch,
-        },
-        "/prod/create-link" => match *event.method() {
-            Method::POST => execute_create_link(&event).await,
-            Method::OPTIONS => preflight(&event),
-            _ => unmatch,
-        },
-        "/prod/delete-link" => match *event.method() {
-            Method::POST => execute_delete_link(&event).await,
-            Method::OPTIONS => preflight(&event),
-            _ => unmatch,
-        },
-        "/prod/search-issues" => match *event.method() {
-            Method::POST => execute_search_issues(&event).await,
-            Method::OPTIONS => preflight(&event),
-            _ => unmatch,
-        },
-        _ => unmatch,
-    }
-}
+    let response = jira_client::with_budget(RetryPolicy::global(), routes().dispatch(&event))
+        .await
+        .unwrap_or_else(|e| e.to_response());
 
-fn event_to_cred(event: &Request) -> JiraAuhtorization {
-    JiraAuhtorization {
-        user_domain: event
-            .headers()
-            .get("x-user-domain")
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string(),
-        email: event
-            .headers()
-            .get("x-user-email")
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string(),
-        jira_token: event
-            .headers()
-            .get("x-user-token")
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string(),
-    }
+    Ok(CorsConfig::global().apply(&event, response))
 }
 
-async fn execute_get_issues(event: &Request) -> Result<Response<Body>, Error> {
-    let json: IssueLoadingRequest = match event.body() {
-        Body::Text(text) => serde_json::from_str(text).map_err(|_| "Invalid format"),
-        _ => Err("Invalid body type"),
-    }?;
-
-    let cred = event_to_cred(event);
+/// table of all routes
+fn routes() -> Router {
+    Router::new(|_| Box::pin(async move { CorsConfig::global().preflight() }))
+        .post("/get-issues", |e| Box::pin(execute_get_issues(e)))
+        .post("/get-issues-by-condition", |e| {
+            Box::pin(execute_get_issues_by_condition(e))
+        })
+        .post("/get-issue-layers", |e| {
+            Box::pin(execute_get_issue_layers(e))
+        })
+        .post("/get-critical-path", |e| {
+            Box::pin(execute_get_critical_path(e))
+        })
+        .post("/create-link", |e| Box::pin(execute_create_link(e)))
+        .post("/delete-link", |e| Box::pin(execute_delete_link(e)))
+        .post("/get-link-types", |e| Box::pin(execute_get_link_types(e)))
+        .post("/search-issues", |e| Box::pin(execute_search_issues(e)))
+        .post("/get-boards", |e| Box::pin(execute_get_boards(e)))
+        .post("/get-sprints", |e| Box::pin(execute_get_sprints(e)))
+        .get("/oauth/authorize", |e| {
+            Box::pin(async move { execute_oauth_authorize(e) })
+        })
+        .post("/oauth/callback", |e| Box::pin(execute_oauth_callback(e)))
+        .post("/oauth/refresh", |e| Box::pin(execute_oauth_refresh(e)))
+}
 
-    let issues = jira_issue_request::load_issue(&json, cred.clone());
+/// parse JSON body of the request
+fn parse_body<T: DeserializeOwned>(event: &Request) -> Result<T, ApiError> {
+    match event.body() {
+        Body::Text(text) => {
+            serde_json::from_str(text).map_err(|e| ApiError::InvalidRequest(e.to_string()))
+        }
+        _ => Err(ApiError::InvalidRequest(String::from("Invalid body type"))),
+    }
+}
 
+/// make JSON response with the value
+fn json_response<T: Serialize>(value: &T) -> Result<Response<Body>, ApiError> {
     // Return something that implements IntoResponse.
     // It will be serialized to the right response event automatically by the runtime
-    let resp = Response::builder()
+    Ok(Response::builder()
         .status(200)
         .header("content-type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
-        .header("Access-Control-Allow-Method", "POST,OPTIONS")
         .body(
-            serde_json::to_string(&issues)
+            serde_json::to_string(value)
                 .expect("unexpected format")
                 .into(),
         )
-        .map_err(Box::new)?;
-    Ok(resp)
+        .expect("should be valid response"))
 }
 
-async fn execute_delete_link(event: &Request) -> Result<Response<Body>, Error> {
-    let json: DeleteLinkRequest = match event.body() {
-        Body::Text(text) => serde_json::from_str(text).map_err(|_| "Invalid format"),
-        _ => Err("Invalid body type"),
-    }?;
+fn event_to_cred(event: &Request) -> Result<JiraCredential, ApiError> {
+    let header = |name: &str| {
+        event
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    let required_header =
+        |name: &str| header(name).ok_or(ApiError::MissingHeader(name.to_string()));
+    // Jira Cloud can select the legacy search API with `offset`
+    let search_api = match header("x-user-search-api").as_deref() {
+        None | Some("jql") => SearchApi::Jql,
+        Some("offset") => SearchApi::Offset,
+        Some(v) => {
+            return Err(ApiError::InvalidRequest(format!(
+                "unknown search API: {}",
+                v
+            )))
+        }
+    };
 
-    let cred = event_to_cred(event);
+    // OAuth 2.0 (3LO) gives cloud id of the site with access token
+    if let Some(cloud_id) = header("x-user-cloud-id") {
+        return Ok(JiraCredential::OAuth(JiraOAuthAuthorization {
+            cloud_id,
+            access_token: required_header("x-user-token")?,
+            search_api,
+        }));
+    }
 
-    delete_link(&json.id, &cred).expect("Not found");
+    // Data Center/Server gives the base URL of their instance instead of domain. It must be in
+    // allow-list to avoid requests to arbitrary hosts
+    if let Some(base_url) = header("x-user-base-url") {
+        ServerBaseUrls::global().check(&base_url)?;
 
-    // Return something that implements IntoResponse.
-    // It will be serialized to the right response event automatically by the runtime
-    let resp = Response::builder()
-        .status(200)
-        .header("content-type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
-        .header("Access-Control-Allow-Method", "POST,OPTIONS")
-        .body(().into())?;
-    Ok(resp)
+        return Ok(JiraCredential::Server(JiraServerAuthorization {
+            base_url,
+            token: required_header("x-user-token")?,
+        }));
+    }
+
+    Ok(JiraCredential::Cloud(JiraAuhtorization {
+        user_domain: required_header("x-user-domain")?,
+        email: required_header("x-user-email")?,
+        jira_token: required_header("x-user-token")?,
+        search_api,
+    }))
 }
 
-async fn execute_create_link(event: &Request) -> Result<Response<Body>, Error> {
-    let json: CreateLinkRequest = match event.body() {
-        Body::Text(text) => serde_json::from_str(text).map_err(|_| "Invalid format"),
-        _ => Err("Invalid body type"),
-    }?;
+async fn execute_get_issues(event: &Request) -> Result<Response<Body>, ApiError> {
+    let json: IssueLoadingRequest = parse_body(event)?;
+    let cred = event_to_cred(event)?;
 
-    let cred = event_to_cred(event);
-    let link = create_link(&json.inward_issue, &json.outward_issue, &cred).expect("Not found");
+    let loaded = jira_issue_request::load_issue(&json, cred).await?;
+    let cycles = IssueGraph::with_link_types(&loaded.issues, &json.link_types).cycles();
 
-    // Return something that implements IntoResponse.
-    // It will be serialized to the right response event automatically by the runtime
-    let resp = Response::builder()
+    json_response(&IssueLoadingResponse {
+        issues: loaded.issues,
+        cycles,
+        report: loaded.report,
+    })
+}
+
+async fn execute_get_issues_by_condition(event: &Request) -> Result<Response<Body>, ApiError> {
+    let json: IssueConditionLoadingRequest = parse_body(event)?;
+    let cred = event_to_cred(event)?;
+
+    let loaded = jira_issue_request::load_issue_by_condition(&json, cred).await?;
+    let cycles = IssueGraph::with_link_types(&loaded.issues, &json.request.link_types).cycles();
+
+    json_response(&IssueLoadingResponse {
+        issues: loaded.issues,
+        cycles,
+        report: loaded.report,
+    })
+}
+
+async fn execute_get_issue_layers(event: &Request) -> Result<Response<Body>, ApiError> {
+    let json: IssueLoadingRequest = parse_body(event)?;
+    let cred = event_to_cred(event)?;
+
+    let loaded = jira_issue_request::load_issue(&json, cred).await?;
+    let graph = IssueGraph::with_link_types(&loaded.issues, &json.link_types);
+
+    json_response(&IssueLayersResponse {
+        layers: graph.layers(),
+        cycles: graph.cycles(),
+        report: loaded.report,
+    })
+}
+
+async fn execute_get_critical_path(event: &Request) -> Result<Response<Body>, ApiError> {
+    let json: CriticalPathRequest = parse_body(event)?;
+    let cred = event_to_cred(event)?;
+
+    let loaded = jira_issue_request::load_issue(&json.request, cred).await?;
+    let issues = loaded.issues;
+    let weights: HashMap<String, f64> = issues
+        .iter()
+        .map(|v| (v.key.clone(), v.estimate_of(json.estimate)))
+        .collect();
+    let graph = IssueGraph::with_link_types(&issues, &json.request.link_types);
+
+    json_response(&CriticalPathResponse {
+        critical_path: graph.critical_path(&weights),
+        cycles: graph.cycles(),
+        issues,
+        report: loaded.report,
+    })
+}
+
+async fn execute_delete_link(event: &Request) -> Result<Response<Body>, ApiError> {
+    let json: DeleteLinkRequest = parse_body(event)?;
+    let cred = event_to_cred(event)?;
+
+    delete_link(&json.id, &cred).await?;
+
+    Ok(Response::builder()
         .status(200)
         .header("content-type", "application/json")
-        .header("Access-Control-Allow-Origin", "*")
-        .header("Access-Control-Allow-Method", "POST,OPTIONS")
-        .body(
-            serde_json::to_string(&link)
-                .expect("unexpected format")
-                .into(),
-        )
-        .map_err(Box::new)?;
-    Ok(resp)
-}
-
-async fn execute_search_issues(event: &Request) -> Result<Response<Body>, Error> {
-    let json: IssueSearchRequest = match event.body() {
-        Body::Text(text) => serde_json::from_str(text).map_err(|_| "Invalid format"),
-        _ => Err("Invalid body type"),
-    }?;
-
-    let cred = event_to_cred(event);
-    let issues = jira_search_request::search_issues(&json, cred.clone());
-
-    match issues {
-        Ok(issues) => {
-            // Return something that implements IntoResponse.
-            // It will be serialized to the right response event automatically by the runtime
-            let resp = Response::builder()
-                .status(200)
-                .header("content-type", "application/json")
-                .header("Access-Control-Allow-Origin", "*")
-                .header("Access-Control-Allow-Method", "POST,OPTIONS")
-                .body(
-                    serde_json::to_string(&issues)
-                        .expect("unexpected format")
-                        .into(),
-                )
-                .unwrap();
-            Ok(resp)
-        }
-        Err(resp) => Ok(resp),
-    }
+        .body(().into())
+        .expect("should be valid response"))
+}
+
+async fn execute_create_link(event: &Request) -> Result<Response<Body>, ApiError> {
+    let json: CreateLinkRequest = parse_body(event)?;
+    let cred = event_to_cred(event)?;
+
+    let link = create_link(
+        &json.inward_issue,
+        &json.outward_issue,
+        &json.link_type,
+        &cred,
+    )
+    .await?;
+
+    json_response(&link)
+}
+
+async fn execute_search_issues(event: &Request) -> Result<Response<Body>, ApiError> {
+    let json: IssueSearchRequest = parse_body(event)?;
+    let cred = event_to_cred(event)?;
+
+    let response = jira_search_request::search_issues(&json, cred).await?;
+
+    json_response(&response)
+}
+
+async fn execute_get_link_types(event: &Request) -> Result<Response<Body>, ApiError> {
+    let cred = event_to_cred(event)?;
+
+    let link_types = jira_link_type_request::get_link_types(cred).await?;
+
+    json_response(&link_types)
 }
 
-fn not_found() -> Result<Response<Body>, Error> {
-    let builder = Response::builder().status(404);
-    let json = json!({});
-    Ok(builder.body(Body::Text(json.to_string()))?)
+async fn execute_get_boards(event: &Request) -> Result<Response<Body>, ApiError> {
+    let json: BoardListRequest = parse_body(event)?;
+    let cred = event_to_cred(event)?;
+
+    let response = jira_sprint_request::get_boards(&json, cred).await?;
+
+    json_response(&response)
 }
 
-fn preflight(event: &Request) -> Result<Response<Body>, Error> {
-    let builder = Response::builder().status(200);
-    let json = json!({});
-    let given_origin = event
+async fn execute_get_sprints(event: &Request) -> Result<Response<Body>, ApiError> {
+    let json: SprintListRequest = parse_body(event)?;
+    let cred = event_to_cred(event)?;
+
+    let response = jira_sprint_request::get_sprints(&json, cred).await?;
+
+    json_response(&response)
+}
+
+fn execute_oauth_authorize(event: &Request) -> Result<Response<Body>, ApiError> {
+    let config = OAuthConfig::from_env()?;
+
+    let state = url::form_urlencoded::parse(event.uri().query().unwrap_or_default().as_bytes())
+        .find(|(k, _)| k == "state")
+        .map(|(_, v)| v.to_string())
+        .unwrap_or_default();
+
+    Ok(Response::builder()
+        .status(302)
+        .header("location", config.get_authorize_url(&state))
+        .body(Body::Empty)
+        .expect("should be valid response"))
+}
+
+async fn execute_oauth_callback(event: &Request) -> Result<Response<Body>, ApiError> {
+    let json: OAuthCallbackRequest = parse_body(event)?;
+    let config = OAuthConfig::from_env()?;
+
+    let token = config.exchange_code(&json.code).await?;
+
+    oauth_token_response(&config, token).await
+}
+
+async fn execute_oauth_refresh(event: &Request) -> Result<Response<Body>, ApiError> {
+    let config = OAuthConfig::from_env()?;
+    let refresh_token = event
         .headers()
-        .get("origin")
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .to_string();
-
-    #[cfg(debug_assertions)]
-    // 8080 for wiremock
-    let origins = vec!["http://localhost:5173", "http://localhost:8080"];
-
-    #[cfg(not(debug_assertions))]
-    let origins = vec![include_str!(".origin-release").trim()];
-
-    let origin = origins.iter().find(|v| v == &&given_origin).unwrap_or(&"");
-
-    Ok(builder
-        .header("Access-Control-Allow-Origin", *origin)
-        .header("Access-Control-Allow-Method", "POST,OPTIONS")
-        .header(
-            "Access-Control-Allow-Headers",
-            "content-type,x-api-key,x-user-domain,x-user-email,x-user-token",
-        )
-        .body(Body::Text(json.to_string()))?)
+        .get_all("cookie")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .find_map(refresh_token_from_cookie)
+        .ok_or(ApiError::Unauthorized)?;
+
+    let token = config.refresh_token(&refresh_token).await?;
+
+    oauth_token_response(&config, token).await
+}
+
+/// make response with token and sites that can be accessed with it. Refresh token is set to
+/// cookie instead of body
+async fn oauth_token_response(
+    config: &OAuthConfig,
+    token: OAuthToken,
+) -> Result<Response<Body>, ApiError> {
+    let resources = config.get_accessible_resources(&token.access_token).await?;
+    let cookie = token.refresh_token.as_deref().map(refresh_token_cookie);
+
+    let mut response = json_response(&OAuthTokenResponse { token, resources })?;
+    if let Some(cookie) = cookie.and_then(|v| HeaderValue::from_str(&v).ok()) {
+        response.headers_mut().insert("set-cookie", cookie);
+    }
+
+    Ok(response)
 }
@@ -0,0 +1,112 @@
+use std::{env, future::Future, pin::Pin};
+
+use isahc::http::Method;
+use lambda_http::{request::RequestContext, Body, Request, Response};
+
+use crate::error::ApiError;
+
+pub type HandlerFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Response<Body>, ApiError>> + Send + 'a>>;
+
+/// function to handle request of a route
+pub type Handler = for<'a> fn(&'a Request) -> HandlerFuture<'a>;
+
+/// default base path when it is not configured and not given from API Gateway
+const DEFAULT_BASE_PATH: &str = "/prod";
+
+struct Route {
+    method: Method,
+    path: String,
+    handler: Handler,
+}
+
+/// Table of routes. Path of routes are relative from base path that is the stage of API Gateway
+/// or `API_BASE_PATH` environment variable.
+pub struct Router {
+    base_path: Option<String>,
+    preflight: Handler,
+    routes: Vec<Route>,
+}
+
+impl Router {
+    /// make router with base path from environment and handler for preflight requests
+    pub fn new(preflight: Handler) -> Router {
+        Router::with_base_path(env::var("API_BASE_PATH").ok(), preflight)
+    }
+
+    /// make router with the base path. Use stage of API Gateway if `base_path` is None
+    pub fn with_base_path(base_path: Option<String>, preflight: Handler) -> Router {
+        Router {
+            base_path: base_path.map(|v| format!("/{}", v.trim_matches('/'))),
+            preflight,
+            routes: Vec::new(),
+        }
+    }
+
+    /// register a route
+    pub fn route(mut self, method: Method, path: &str, handler: Handler) -> Router {
+        self.routes.push(Route {
+            method,
+            path: format!("/{}", path.trim_matches('/')),
+            handler,
+        });
+        self
+    }
+
+    /// register a route for GET
+    pub fn get(self, path: &str, handler: Handler) -> Router {
+        self.route(Method::GET, path, handler)
+    }
+
+    /// register a route for POST. Preflight request is also accepted for the path
+    pub fn post(self, path: &str, handler: Handler) -> Router {
+        let preflight = self.preflight;
+
+        self.route(Method::POST, path, handler)
+            .route(Method::OPTIONS, path, preflight)
+    }
+
+    /// get base path for the request
+    fn base_path(&self, event: &Request) -> String {
+        if let Some(base_path) = &self.base_path {
+            return base_path.clone();
+        }
+
+        let stage = match event.extensions().get::<RequestContext>() {
+            Some(RequestContext::ApiGatewayV1(context)) => context.stage.clone(),
+            _ => None,
+        };
+
+        match stage {
+            Some(stage) if stage != "$default" => format!("/{}", stage),
+            _ => String::from(DEFAULT_BASE_PATH),
+        }
+    }
+
+    /// call handler of the route matched with the request
+    pub async fn dispatch(&self, event: &Request) -> Result<Response<Body>, ApiError> {
+        let base_path = self.base_path(event);
+        let path = event
+            .uri()
+            .path()
+            .strip_prefix(base_path.trim_end_matches('/'))
+            .filter(|v| v.is_empty() || v.starts_with('/'))
+            .map(|v| format!("/{}", v.trim_matches('/')));
+
+        let routes: Vec<&Route> = match path {
+            Some(path) => self.routes.iter().filter(|v| v.path == path).collect(),
+            None => Vec::new(),
+        };
+
+        if routes.is_empty() {
+            return Err(ApiError::RouteNotFound(event.uri().path().to_string()));
+        }
+
+        match routes.iter().find(|v| v.method == event.method()) {
+            Some(route) => (route.handler)(event).await,
+            None => Err(ApiError::MethodNotAllowed(
+                routes.iter().map(|v| v.method.to_string()).collect(),
+            )),
+        }
+    }
+}
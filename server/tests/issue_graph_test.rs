@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use jira_issue_loader::{
+    issue::{JiraIssue, JiraIssueLink, JiraIssueLinkType},
+    issue_graph::{IssueCycle, IssueGraph},
+};
+
+fn issue(key: &str, links: &[(&str, &str, &str)]) -> JiraIssue {
+    JiraIssue {
+        key: key.to_string(),
+        links: links
+            .iter()
+            .map(|(id, inward, outward)| JiraIssueLink {
+                id: id.to_string(),
+                inward_issue: inward.to_string(),
+                outward_issue: outward.to_string(),
+                link_type: None,
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn link_type(name: &str) -> JiraIssueLinkType {
+    JiraIssueLinkType {
+        id: name.to_string(),
+        name: name.to_string(),
+        inward: String::default(),
+        outward: String::default(),
+    }
+}
+
+#[test]
+fn no_cycles_in_chain() {
+    // arrange
+    let issues = vec![
+        issue("A", &[("1", "A", "B")]),
+        issue("B", &[("1", "A", "B"), ("2", "B", "C")]),
+        issue("C", &[("2", "B", "C")]),
+    ];
+
+    // do
+    let cycles = IssueGraph::new(&issues).cycles();
+
+    // verify
+    assert!(cycles.is_empty());
+}
+
+#[test]
+fn detect_loop_of_issues() {
+    // arrange
+    let issues = vec![
+        issue("A", &[("1", "A", "B"), ("3", "C", "A")]),
+        issue("B", &[("1", "A", "B"), ("2", "B", "C")]),
+        issue("C", &[("2", "B", "C"), ("3", "C", "A"), ("4", "C", "D")]),
+        issue("D", &[("4", "C", "D")]),
+    ];
+
+    // do
+    let cycles = IssueGraph::new(&issues).cycles();
+
+    // verify
+    assert_eq!(
+        cycles,
+        vec![IssueCycle {
+            issues: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            links: vec!["1".to_string(), "2".to_string(), "3".to_string()],
+        }]
+    );
+}
+
+#[test]
+fn detect_multiple_loops_and_self_link() {
+    // arrange
+    let issues = vec![
+        issue("A", &[("1", "A", "B"), ("2", "B", "A")]),
+        issue("C", &[("3", "C", "C")]),
+        issue("D", &[("4", "B", "D")]),
+    ];
+
+    // do
+    let cycles = IssueGraph::new(&issues).cycles();
+
+    // verify
+    assert_eq!(
+        cycles,
+        vec![
+            IssueCycle {
+                issues: vec!["A".to_string(), "B".to_string()],
+                links: vec!["1".to_string(), "2".to_string()],
+            },
+            IssueCycle {
+                issues: vec!["C".to_string()],
+                links: vec!["3".to_string()],
+            }
+        ]
+    );
+}
+
+#[test]
+fn layers_by_longest_path_from_root() {
+    // arrange
+    let issues = vec![
+        issue("A", &[("1", "A", "B"), ("2", "A", "C")]),
+        issue("B", &[("3", "B", "C")]),
+        issue("C", &[]),
+        issue("D", &[]),
+    ];
+
+    // do
+    let layers = IssueGraph::new(&issues).layers();
+
+    // verify
+    assert_eq!(layers.order, vec!["A", "D", "B", "C"]);
+    assert_eq!(layers.layers["A"], 0);
+    assert_eq!(layers.layers["B"], 1);
+    assert_eq!(layers.layers["C"], 2);
+    assert_eq!(layers.layers["D"], 0);
+}
+
+#[test]
+fn issues_in_loop_share_layer() {
+    // arrange
+    let issues = vec![
+        issue("A", &[("1", "A", "B")]),
+        issue("B", &[("2", "B", "C"), ("3", "C", "B")]),
+        issue("D", &[("4", "C", "D")]),
+    ];
+
+    // do
+    let layers = IssueGraph::new(&issues).layers();
+
+    // verify
+    assert_eq!(layers.order, vec!["A", "B", "C", "D"]);
+    assert_eq!(layers.layers["B"], 1);
+    assert_eq!(layers.layers["C"], 1);
+    assert_eq!(layers.layers["D"], 2);
+}
+
+#[test]
+fn critical_path_is_longest_weighted_chain() {
+    // arrange
+    let issues = vec![
+        issue("A", &[("1", "A", "B"), ("2", "A", "C")]),
+        issue("B", &[("3", "B", "D")]),
+        issue("C", &[("4", "C", "D")]),
+        issue("D", &[]),
+    ];
+    let weights = HashMap::from([
+        ("A".to_string(), 1.0),
+        ("B".to_string(), 5.0),
+        ("C".to_string(), 2.0),
+        ("D".to_string(), 3.0),
+    ]);
+
+    // do
+    let critical_path = IssueGraph::new(&issues).critical_path(&weights);
+
+    // verify
+    assert_eq!(critical_path.path, vec!["A", "B", "D"]);
+    assert_eq!(critical_path.duration, 9.0);
+    assert_eq!(critical_path.slack["A"], 0.0);
+    assert_eq!(critical_path.slack["B"], 0.0);
+    assert_eq!(critical_path.slack["C"], 3.0);
+    assert_eq!(critical_path.slack["D"], 0.0);
+}
+
+#[test]
+fn ignore_links_not_in_given_types() {
+    // arrange
+    let mut issues = vec![
+        issue("A", &[("1", "A", "B")]),
+        issue("B", &[("2", "B", "A")]),
+    ];
+    issues[0].links[0].link_type = Some(link_type("Blocks"));
+    issues[1].links[0].link_type = Some(link_type("Relates"));
+
+    // do
+    let all = IssueGraph::new(&issues).cycles();
+    let blocks = IssueGraph::with_link_types(&issues, &["Blocks".to_string()]);
+
+    // verify
+    assert_eq!(all.len(), 1);
+    assert!(blocks.cycles().is_empty());
+    assert_eq!(blocks.layers().layers["B"], 1);
+}
+
+#[test]
+fn use_blocks_links_without_given_types() {
+    // arrange
+    let mut issues = vec![
+        issue("A", &[("1", "A", "B")]),
+        issue("B", &[("2", "B", "A")]),
+    ];
+    issues[0].links[0].link_type = Some(link_type("Blocks"));
+    issues[1].links[0].link_type = Some(link_type("Relates"));
+
+    // do
+    let graph = IssueGraph::with_link_types(&issues, &[]);
+
+    // verify
+    assert!(graph.cycles().is_empty());
+    assert_eq!(graph.layers().layers["B"], 1);
+}
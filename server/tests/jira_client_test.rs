@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use httpmock::{Method, MockServer};
+use isahc::Request;
+use jira_issue_loader::{
+    error::ApiError,
+    jira_client::{send_non_idempotent_with_policy, send_with_policy, with_budget, RetryPolicy},
+};
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 2,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+        budget: Duration::from_secs(5),
+    }
+}
+
+#[tokio::test]
+async fn retry_while_jira_is_unavailable() {
+    // arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET).path("/rest/api/3/issueLinkType");
+
+        then.status(503);
+    });
+
+    // do
+    let url = server.url("/rest/api/3/issueLinkType");
+    let result = send_with_policy(&policy(), || Request::get(&url).body(())).await;
+
+    // verify
+    mock.assert_hits(3);
+    assert_eq!(result.err(), Some(ApiError::JiraUnavailable(503)));
+}
+
+#[tokio::test]
+async fn do_not_retry_client_error() {
+    // arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET).path("/rest/api/3/issueLinkType");
+
+        then.status(401);
+    });
+
+    // do
+    let url = server.url("/rest/api/3/issueLinkType");
+    let result = send_with_policy(&policy(), || Request::get(&url).body(())).await;
+
+    // verify
+    mock.assert_hits(1);
+    assert_eq!(result.err(), Some(ApiError::Unauthorized));
+}
+
+#[tokio::test]
+async fn give_up_if_retry_after_exceeds_budget() {
+    // arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET).path("/rest/api/3/issueLinkType");
+
+        then.status(429).header("retry-after", "60");
+    });
+
+    // do
+    let url = server.url("/rest/api/3/issueLinkType");
+    let result = send_with_policy(&policy(), || Request::get(&url).body(())).await;
+
+    // verify
+    mock.assert_hits(1);
+    assert_eq!(
+        result.err(),
+        Some(ApiError::RateLimited {
+            retry_after: Some(60)
+        })
+    );
+}
+
+#[tokio::test]
+async fn wait_for_retry_after() {
+    // arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET).path("/rest/api/3/issueLinkType");
+
+        then.status(429).header("retry-after", "0");
+    });
+
+    // do
+    let url = server.url("/rest/api/3/issueLinkType");
+    let result = send_with_policy(&policy(), || Request::get(&url).body(())).await;
+
+    // verify
+    mock.assert_hits(3);
+    assert_eq!(
+        result.err(),
+        Some(ApiError::RateLimited {
+            retry_after: Some(0)
+        })
+    );
+}
+
+#[tokio::test]
+async fn do_not_retry_non_idempotent_request_on_bad_gateway() {
+    // arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/issueLink");
+
+        then.status(502);
+    });
+
+    // do
+    let url = server.url("/rest/api/3/issueLink");
+    let result =
+        send_non_idempotent_with_policy(&policy(), || Request::post(&url).body("{}")).await;
+
+    // verify
+    mock.assert_hits(1);
+    assert_eq!(result.err(), Some(ApiError::JiraUnavailable(502)));
+}
+
+#[tokio::test]
+async fn retry_non_idempotent_request_while_jira_is_unavailable() {
+    // arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/issueLink");
+
+        then.status(503);
+    });
+
+    // do
+    let url = server.url("/rest/api/3/issueLink");
+    let result =
+        send_non_idempotent_with_policy(&policy(), || Request::post(&url).body("{}")).await;
+
+    // verify
+    mock.assert_hits(3);
+    assert_eq!(result.err(), Some(ApiError::JiraUnavailable(503)));
+}
+
+#[tokio::test]
+async fn share_budget_between_requests() {
+    // arrange
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET).path("/rest/api/3/issueLinkType");
+
+        then.status(200).delay(Duration::from_millis(300));
+    });
+    let policy = RetryPolicy {
+        budget: Duration::from_millis(500),
+        ..policy()
+    };
+
+    // do
+    let url = server.url("/rest/api/3/issueLinkType");
+    let (first, second) = with_budget(&policy, async {
+        let first = send_with_policy(&policy, || Request::get(&url).body(())).await;
+        let second = send_with_policy(&policy, || Request::get(&url).body(())).await;
+
+        (first, second)
+    })
+    .await;
+
+    // verify
+    mock.assert_hits(2);
+    assert!(first.is_ok());
+    assert_eq!(
+        second.err(),
+        Some(ApiError::Network(String::from(
+            "deadline of requests exceeded"
+        )))
+    );
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use httpmock::{Method, MockServer};
+use jira_issue_loader::{error::ApiError, jira_filter_request::get_filter_jql, jira_url::JiraUrl};
+
+struct TestRequest<'a> {
+    server: &'a MockServer,
+}
+impl JiraUrl for TestRequest<'_> {
+    fn get_url(&self, path: &str) -> String {
+        self.server.url(path)
+    }
+
+    fn get_base_headers(&self) -> std::collections::HashMap<String, String> {
+        let mut map = HashMap::new();
+        map.insert("authorization".to_string(), "foo".to_string());
+        map
+    }
+}
+
+#[tokio::test]
+async fn get_jql_of_filter() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/rest/api/3/filter/10000")
+            .header("authorization", "foo");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "id": "10000",
+                "jql": "project = PROJ"
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let result = get_filter_jql(10000, &url).await;
+
+    // verify
+    assert_eq!(result, Ok("project = PROJ".to_string()));
+    mock.assert();
+}
+
+#[tokio::test]
+async fn reject_filter_without_jql() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::GET).path("/rest/api/3/filter/10000");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "id": "10000",
+                "jql": ""
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let result = get_filter_jql(10000, &url).await;
+
+    // verify
+    assert_eq!(
+        result,
+        Err(ApiError::InvalidRequest(
+            "filter 10000 does not have JQL".to_string()
+        ))
+    );
+}
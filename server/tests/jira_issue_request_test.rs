@@ -2,10 +2,18 @@ use std::collections::{HashMap, HashSet};
 
 use httpmock::{Method, MockServer};
 use jira_issue_loader::{
-    api_type::IssueLoadingRequest,
-    issue::{JiraIssue, JiraIssueLink, JiraStatus},
-    jira_issue_request::load_issue,
-    jira_url::JiraUrl,
+    api_type::{IssueConditionLoadingRequest, IssueLoadingRequest, IssueSearchCondition},
+    error::ApiError,
+    issue::{
+        JiraComponent, JiraIssue, JiraIssueLink, JiraIssueParent, JiraIssueType, JiraPriority,
+        JiraStatus, JiraUser, JiraVersion,
+    },
+    jira_issue_request::{
+        load_issue, load_issue_by_condition, load_issue_with_config, ChunkFailure, LoadingConfig,
+        LoadingReport,
+    },
+    jira_url::{JiraUrl, SearchApi},
+    jql::{IssueKey, IssueKeyPattern},
 };
 
 struct TestRequest<'a> {
@@ -23,8 +31,26 @@ impl JiraUrl for TestRequest<'_> {
     }
 }
 
-#[test]
-fn request_to_get_an_issue() {
+/// request with `search/jql` API
+struct TestJqlRequest<'a> {
+    server: &'a MockServer,
+}
+impl JiraUrl for TestJqlRequest<'_> {
+    fn get_url(&self, path: &str) -> String {
+        self.server.url(path)
+    }
+
+    fn get_base_headers(&self) -> std::collections::HashMap<String, String> {
+        HashMap::new()
+    }
+
+    fn search_api(&self) -> SearchApi {
+        SearchApi::Jql
+    }
+}
+
+#[tokio::test]
+async fn request_to_get_an_issue() {
     // arrange
     let server = httpmock::MockServer::start();
     let _mock = server.mock(|when, then| {
@@ -32,7 +58,7 @@ fn request_to_get_an_issue() {
             .path("/rest/api/3/search")
             .header("content-type", "application/json")
             .header("authorization", "foo")
-            .body_contains("key-1");
+            .body_contains("KEY-1");
 
         then.status(200)
             .header("content-type", "application/json")
@@ -85,8 +111,9 @@ fn request_to_get_an_issue() {
     let url = TestRequest { server: &server };
     let request = IssueLoadingRequest {
         issues: vec!["key-1".to_string()],
+        ..Default::default()
     };
-    let result = load_issue(&request, url);
+    let result = load_issue(&request, url).await.unwrap().issues;
 
     // verify
     assert_eq!(result.len(), 1);
@@ -105,13 +132,14 @@ fn request_to_get_an_issue() {
         JiraIssueLink {
             id: "100".to_string(),
             inward_issue: "test".to_string(),
-            outward_issue: "other".to_string()
+            outward_issue: "other".to_string(),
+            link_type: None,
         }
     );
 }
 
-#[test]
-fn request_to_get_simplest_issue() {
+#[tokio::test]
+async fn request_to_get_simplest_issue() {
     // arrange
     let server = httpmock::MockServer::start();
     let mock = server.mock(|when, then| {
@@ -139,8 +167,9 @@ fn request_to_get_simplest_issue() {
     let url = TestRequest { server: &server };
     let request = IssueLoadingRequest {
         issues: vec!["key-1".to_string()],
+        ..Default::default()
     };
-    let result = load_issue(&request, url);
+    let result = load_issue(&request, url).await.unwrap().issues;
 
     // verify
     mock.assert();
@@ -152,8 +181,8 @@ fn request_to_get_simplest_issue() {
     assert_eq!(result[0].links.len(), 0);
 }
 
-#[test]
-fn request_to_get_simplest_issue_with_subtasks() {
+#[tokio::test]
+async fn request_to_get_simplest_issue_with_subtasks() {
     // arrange
     let server = httpmock::MockServer::start();
     let _mock = server.mock(|when, then| {
@@ -161,7 +190,7 @@ fn request_to_get_simplest_issue_with_subtasks() {
             .path("/rest/api/3/search")
             .header("content-type", "application/json")
             .header("authorization", "foo")
-            .body_contains("test");
+            .body_contains("TEST-1");
 
         then.status(200)
             .header("content-type", "application/json")
@@ -182,9 +211,10 @@ fn request_to_get_simplest_issue_with_subtasks() {
     // do
     let url = TestRequest { server: &server };
     let request = IssueLoadingRequest {
-        issues: vec!["test".to_string()],
+        issues: vec!["TEST-1".to_string()],
+        ..Default::default()
     };
-    let mut result = load_issue(&request, url);
+    let mut result = load_issue(&request, url).await.unwrap().issues;
 
     result.sort_by(|o1, o2| o1.key.cmp(&o2.key).reverse());
     // verify
@@ -206,8 +236,8 @@ fn request_to_get_simplest_issue_with_subtasks() {
     assert_eq!(result[0].subtasks[0], "key-2");
 }
 
-#[test]
-fn request_recursive() {
+#[tokio::test]
+async fn request_recursive() {
     // arrange
     let server = httpmock::MockServer::start();
     let mock = server.mock(|when, then| {
@@ -237,12 +267,12 @@ fn request_recursive() {
     // do
     let url = TestRequest { server: &server };
     let request = IssueLoadingRequest {
-        issues: (1..51).into_iter().map(|v| format!("key-{}", v)).collect(),
+        issues: (1..51).map(|v| format!("key-{}", v)).collect(),
+        ..Default::default()
     };
-    let ret = load_issue(&request, url);
+    let ret = load_issue(&request, url).await.unwrap().issues;
     let keys = ret.into_iter().map(|v| v.key).collect::<HashSet<String>>();
     let expected = (0..=50)
-        .into_iter()
         .map(|v| format!("test{}", v))
         .collect::<HashSet<String>>();
 
@@ -251,3 +281,1021 @@ fn request_recursive() {
     assert_eq!(keys.len(), 51);
     assert_eq!(keys.difference(&expected).count(), 0);
 }
+
+#[tokio::test]
+async fn request_rest_pages_concurrently() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::GET).path("/rest/api/3/filter/10000");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({"id": "10000", "jql": "project = PROJ"}));
+    });
+    let mocks = (0..3)
+        .map(|page| {
+            server.mock(|when, then| {
+                when.method(Method::POST)
+                    .path("/rest/api/3/search")
+                    .json_body_partial(format!("{{\"startAt\": {}, \"maxResults\": 2}}", page * 2));
+
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .json_body(serde_json::json!({
+                        "total": 5,
+                        "issues": (page * 2..(page * 2 + 2).min(5))
+                            .map(|v| serde_json::json!({
+                                "key": format!("key-{}", v),
+                                "fields": {"summary": ""}
+                            }))
+                            .collect::<Vec<_>>()
+                    }));
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        filter: Some(10000),
+        ..Default::default()
+    };
+    let config = LoadingConfig {
+        page_size: 2,
+        concurrency: 2,
+        ..Default::default()
+    };
+    let result = load_issue_with_config(&request, &config, url)
+        .await
+        .unwrap();
+    let keys = result
+        .issues
+        .into_iter()
+        .map(|v| v.key)
+        .collect::<HashSet<String>>();
+
+    // verify
+    mocks.iter().for_each(|v| v.assert());
+    assert_eq!(
+        keys,
+        (0..5)
+            .map(|v| format!("key-{}", v))
+            .collect::<HashSet<String>>()
+    );
+}
+
+#[tokio::test]
+async fn report_failed_chunks_of_keys() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let chunk_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"KEY-1\", \"KEY-2\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 2,
+                "issues": [
+                    {"key": "KEY-1", "fields": {"summary": ""}},
+                    {"key": "KEY-2", "fields": {"summary": ""}}
+                ]
+            }));
+    });
+    let failed_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"KEY-3\")"#);
+
+        then.status(500);
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec![
+            "KEY-1".to_string(),
+            "KEY-2".to_string(),
+            "KEY-3".to_string(),
+        ],
+        ..Default::default()
+    };
+    let config = LoadingConfig {
+        chunk_size: 2,
+        ..Default::default()
+    };
+    let result = load_issue_with_config(&request, &config, url)
+        .await
+        .unwrap();
+    let keys = result
+        .issues
+        .into_iter()
+        .map(|v| v.key)
+        .collect::<HashSet<String>>();
+
+    // verify
+    chunk_mock.assert();
+    failed_mock.assert();
+    assert_eq!(
+        keys,
+        HashSet::from(["KEY-1".to_string(), "KEY-2".to_string()])
+    );
+    assert_eq!(
+        result.report.warnings,
+        vec![ChunkFailure {
+            keys: vec![IssueKey::parse("KEY-3", &IssueKeyPattern::default()).unwrap()],
+            error: ApiError::JiraUnavailable(500),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn report_keys_rejected_by_jira_as_missing() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let rejected_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains("BAD-1");
+
+        then.status(400)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "errorMessages": ["An issue with key 'BAD-1' does not exist for field 'key'."]
+            }));
+    });
+    let first_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"KEY-1\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [{"key": "KEY-1", "fields": {"summary": ""}}]
+            }));
+    });
+    let second_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"KEY-2\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [{"key": "KEY-2", "fields": {"summary": ""}}]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec![
+            "KEY-1".to_string(),
+            "KEY-2".to_string(),
+            "BAD-1".to_string(),
+        ],
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap();
+    let keys = result
+        .issues
+        .into_iter()
+        .map(|v| v.key)
+        .collect::<HashSet<String>>();
+
+    // verify
+    rejected_mock.assert_hits(3);
+    first_mock.assert();
+    second_mock.assert();
+    assert_eq!(
+        keys,
+        HashSet::from(["KEY-1".to_string(), "KEY-2".to_string()])
+    );
+    assert_eq!(
+        result.report,
+        LoadingReport {
+            missing_keys: vec![IssueKey::parse("BAD-1", &IssueKeyPattern::default()).unwrap()],
+            ..Default::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn reject_invalid_issue_key() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/search");
+
+        then.status(200);
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["KEY-1) OR project = SECRET OR key in (KEY-2".to_string()],
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await;
+
+    // verify
+    mock.assert_hits(0);
+    assert!(matches!(result, Err(ApiError::InvalidRequest(_))));
+}
+
+#[tokio::test]
+async fn return_error_if_all_chunks_failed() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/search");
+
+        then.status(401);
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["key-1".to_string()],
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await;
+
+    // verify
+    assert_eq!(result.err(), Some(ApiError::Unauthorized));
+}
+
+#[tokio::test]
+async fn load_all_pages_with_page_token() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let first_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search/jql")
+            .matches(|req| {
+                !String::from_utf8_lossy(req.body.as_deref().unwrap_or_default())
+                    .contains("nextPageToken")
+            });
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "issues": [{"key": "KEY-1", "fields": {"summary": ""}}],
+                "nextPageToken": "token-2",
+                "isLast": false
+            }));
+    });
+    let second_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search/jql")
+            .json_body_partial(r#"{"nextPageToken": "token-2"}"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "issues": [{"key": "KEY-2", "fields": {"summary": ""}}],
+                "isLast": true
+            }));
+    });
+
+    // do
+    let url = TestJqlRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["KEY-1".to_string(), "KEY-2".to_string()],
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap();
+    let keys = result
+        .issues
+        .into_iter()
+        .map(|v| v.key)
+        .collect::<HashSet<String>>();
+
+    // verify
+    first_mock.assert();
+    second_mock.assert();
+    assert_eq!(
+        keys,
+        HashSet::from(["KEY-1".to_string(), "KEY-2".to_string()])
+    );
+}
+
+fn mock_linked_issue(server: &MockServer, key: &str, linked: &str) {
+    server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(format!(r#"(\"{}\")"#, key));
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {
+                        "key": key,
+                        "fields": {
+                            "summary": key,
+                            "issuelinks": [
+                                {
+                                    "id": format!("{}-{}", key, linked),
+                                    "outwardIssue": {
+                                        "key": linked
+                                    }
+                                }
+                            ]
+                        }
+                    }
+                ]
+            }));
+    });
+}
+
+#[tokio::test]
+async fn follow_links_until_depth() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    mock_linked_issue(&server, "A-1", "B-1");
+    mock_linked_issue(&server, "B-1", "C-1");
+    mock_linked_issue(&server, "C-1", "D-1");
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["A-1".to_string()],
+        depth: 2,
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap().issues;
+    let keys = result
+        .into_iter()
+        .map(|v| v.key)
+        .collect::<HashSet<String>>();
+
+    // verify
+    assert_eq!(
+        keys,
+        HashSet::from(["A-1".to_string(), "B-1".to_string(), "C-1".to_string()])
+    );
+}
+
+#[tokio::test]
+async fn stop_following_links_with_max_issues() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    mock_linked_issue(&server, "A-1", "B-1");
+    mock_linked_issue(&server, "B-1", "C-1");
+    mock_linked_issue(&server, "C-1", "D-1");
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["A-1".to_string()],
+        depth: 5,
+        max_issues: Some(2),
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap();
+    let keys = result
+        .issues
+        .into_iter()
+        .map(|v| v.key)
+        .collect::<HashSet<String>>();
+
+    // verify
+    assert!(keys.len() <= 2);
+    assert_eq!(keys, HashSet::from(["A-1".to_string(), "B-1".to_string()]));
+    assert!(result.report.truncated);
+}
+
+#[tokio::test]
+async fn count_subtasks_of_linked_issues_against_max_issues() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    mock_linked_issue(&server, "A-1", "B-1");
+    server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"(\"B-1\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {
+                        "key": "B-1",
+                        "fields": {
+                            "summary": "",
+                            "subtasks": [{"key": "B-2"}, {"key": "B-3"}]
+                        }
+                    }
+                ]
+            }));
+    });
+    let subtask_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"(\"B-2\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [{"key": "B-2", "fields": {"summary": ""}}]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["A-1".to_string()],
+        depth: 5,
+        max_issues: Some(3),
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap();
+    let keys = result
+        .issues
+        .into_iter()
+        .map(|v| v.key)
+        .collect::<HashSet<String>>();
+
+    // verify
+    subtask_mock.assert();
+    assert_eq!(
+        keys,
+        HashSet::from(["A-1".to_string(), "B-1".to_string(), "B-2".to_string()])
+    );
+    assert!(result.report.truncated);
+}
+
+#[tokio::test]
+async fn report_keys_jira_did_not_return() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/search");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {"key": "KEY-1", "fields": {"summary": ""}}
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["KEY-1".to_string(), "KEY-2".to_string()],
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap();
+
+    // verify
+    assert_eq!(result.issues.len(), 1);
+    assert_eq!(
+        result.report,
+        LoadingReport {
+            missing_keys: vec![IssueKey::parse("KEY-2", &IssueKeyPattern::default()).unwrap()],
+            warnings: vec![],
+            truncated: false,
+        }
+    );
+}
+
+#[tokio::test]
+async fn load_issues_in_sprint_and_epic() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/rest/api/3/jql/autocompletedata/suggestions")
+            .query_param("fieldValue", "Sprint 42");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "results": [
+                    {"value": "42", "displayName": "<b>Sprint 42</b> (Board)"}
+                ]
+            }));
+    });
+    let scope = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(
+                r#"(parent = \"EPIC-1\" OR \"Epic Link\" = \"EPIC-1\") AND sprint in (42)"#,
+            );
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {
+                        "key": "KEY-1",
+                        "fields": {
+                            "summary": "",
+                            "issuelinks": [
+                                {"id": "100", "outwardIssue": {"key": "KEY-2"}}
+                            ]
+                        }
+                    }
+                ]
+            }));
+    });
+    let linked = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"KEY-2\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {"key": "KEY-2", "fields": {"summary": ""}}
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueConditionLoadingRequest {
+        condition: IssueSearchCondition {
+            sprint: Some("Sprint 42".to_string()),
+            epic: Some("EPIC-1".to_string()),
+        },
+        request: IssueLoadingRequest {
+            depth: 1,
+            ..Default::default()
+        },
+    };
+    let result = load_issue_by_condition(&request, url).await.unwrap();
+
+    // verify
+    let mut keys = result
+        .issues
+        .iter()
+        .map(|v| v.key.clone())
+        .collect::<Vec<String>>();
+    keys.sort();
+    assert_eq!(keys, vec!["KEY-1", "KEY-2"]);
+    assert_eq!(result.report, LoadingReport::default());
+    scope.assert();
+    linked.assert();
+}
+
+#[tokio::test]
+async fn reject_empty_condition() {
+    // arrange
+    let server = httpmock::MockServer::start();
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueConditionLoadingRequest::default();
+    let result = load_issue_by_condition(&request, url).await;
+
+    // verify
+    assert_eq!(
+        result.err(),
+        Some(ApiError::InvalidRequest(
+            "sprint or epic is required".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn load_issues_of_saved_filter_with_subtasks() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let filter = server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/rest/api/3/filter/10000")
+            .header("authorization", "foo");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "id": "10000",
+                "name": "team filter",
+                "jql": "project = PROJ ORDER BY Rank ASC"
+            }));
+    });
+    let scope = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#""jql":"(project = PROJ)""#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {
+                        "key": "KEY-1",
+                        "fields": {
+                            "summary": "",
+                            "subtasks": [{"key": "KEY-2"}]
+                        }
+                    }
+                ]
+            }));
+    });
+    let subtasks = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"KEY-2\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {"key": "KEY-2", "fields": {"summary": ""}}
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        filter: Some(10000),
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap();
+
+    // verify
+    let mut keys = result
+        .issues
+        .iter()
+        .map(|v| v.key.clone())
+        .collect::<Vec<String>>();
+    keys.sort();
+    assert_eq!(keys, vec!["KEY-1", "KEY-2"]);
+    assert_eq!(result.report, LoadingReport::default());
+    filter.assert();
+    scope.assert();
+    subtasks.assert();
+}
+
+#[tokio::test]
+async fn request_to_get_rich_fields_of_issue() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains("fixVersions")
+            .body_contains("assignee");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {
+                        "key": "KEY-1",
+                        "fields": {
+                            "summary": "",
+                            "assignee": {
+                                "accountId": "account-1",
+                                "displayName": "user 1",
+                                "avatarUrls": {"48x48": "https://avatar.url"}
+                            },
+                            "reporter": {
+                                "name": "user2",
+                                "displayName": "user 2"
+                            },
+                            "priority": {"id": "3", "name": "Medium", "iconUrl": "https://icon.url"},
+                            "labels": ["backend", "team-a"],
+                            "components": [{"id": "10", "name": "server"}],
+                            "fixVersions": [
+                                {"id": "20", "name": "1.0.0", "released": true, "releaseDate": "2024-01-01"}
+                            ]
+                        }
+                    }
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["KEY-1".to_string()],
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap().issues;
+
+    // verify
+    mock.assert();
+    assert_eq!(
+        result[0].assignee,
+        Some(JiraUser {
+            id: "account-1".to_string(),
+            display_name: "user 1".to_string(),
+            avatar_url: Some("https://avatar.url".to_string()),
+        })
+    );
+    assert_eq!(
+        result[0].reporter,
+        Some(JiraUser {
+            id: "user2".to_string(),
+            display_name: "user 2".to_string(),
+            avatar_url: None,
+        })
+    );
+    assert_eq!(
+        result[0].priority,
+        Some(JiraPriority {
+            id: "3".to_string(),
+            name: "Medium".to_string(),
+            icon_url: Some("https://icon.url".to_string()),
+        })
+    );
+    assert_eq!(result[0].labels, vec!["backend", "team-a"]);
+    assert_eq!(
+        result[0].components,
+        vec![JiraComponent {
+            id: "10".to_string(),
+            name: "server".to_string(),
+        }]
+    );
+    assert_eq!(
+        result[0].fix_versions,
+        vec![JiraVersion {
+            id: "20".to_string(),
+            name: "1.0.0".to_string(),
+            released: true,
+            release_date: Some("2024-01-01".to_string()),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn load_parents_up_to_top_of_hierarchy() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"KEY-1\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {
+                        "key": "KEY-1",
+                        "fields": {
+                            "summary": "",
+                            "parent": {
+                                "key": "EPIC-1",
+                                "fields": {
+                                    "summary": "epic",
+                                    "issuetype": {"id": "1", "name": "Epic"}
+                                }
+                            }
+                        }
+                    }
+                ]
+            }));
+    });
+    let epic = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"EPIC-1\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {
+                        "key": "EPIC-1",
+                        "fields": {
+                            "summary": "epic",
+                            "parent": {"key": "INIT-1"}
+                        }
+                    }
+                ]
+            }));
+    });
+    let initiative = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"INIT-1\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {"key": "INIT-1", "fields": {"summary": "initiative"}}
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["KEY-1".to_string()],
+        load_parents: true,
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap();
+
+    // verify
+    let mut issues = result.issues;
+    issues.sort_by(|o1, o2| o1.key.cmp(&o2.key));
+    assert_eq!(
+        issues.iter().map(|v| v.key.as_str()).collect::<Vec<&str>>(),
+        vec!["EPIC-1", "INIT-1", "KEY-1"]
+    );
+    assert_eq!(
+        issues[2].parent,
+        Some(JiraIssueParent {
+            key: "EPIC-1".to_string(),
+            issue_type: Some(JiraIssueType {
+                id: "1".to_string(),
+                name: "Epic".to_string(),
+                avatar_url: None,
+            }),
+            summary: Some("epic".to_string()),
+        })
+    );
+    assert_eq!(result.report, LoadingReport::default());
+    epic.assert();
+    initiative.assert();
+}
+
+#[tokio::test]
+async fn stop_loading_parents_by_max_issues() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"KEY-1\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {"key": "KEY-1", "fields": {"summary": "", "parent": {"key": "EPIC-1"}}}
+                ]
+            }));
+    });
+    let epic = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains("EPIC-1");
+
+        then.status(500);
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["KEY-1".to_string()],
+        max_issues: Some(1),
+        load_parents: true,
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap();
+
+    // verify
+    assert_eq!(result.issues.len(), 1);
+    assert!(result.report.truncated);
+    epic.assert_hits(0);
+}
+
+#[tokio::test]
+async fn read_story_points_from_configured_field() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains("customfield_10028");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {
+                        "key": "KEY-1",
+                        "fields": {
+                            "summary": "",
+                            "customfield_10016": 1.0,
+                            "customfield_10028": 5.0
+                        }
+                    }
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["KEY-1".to_string()],
+        ..Default::default()
+    };
+    let config = LoadingConfig {
+        story_points_field: "customfield_10028".to_string(),
+        ..Default::default()
+    };
+    let result = load_issue_with_config(&request, &config, url)
+        .await
+        .unwrap()
+        .issues;
+
+    // verify
+    mock.assert();
+    assert_eq!(
+        result[0].estimate.as_ref().and_then(|v| v.story_points),
+        Some(5.0)
+    );
+}
+
+#[tokio::test]
+async fn skip_malformed_issue_with_warning() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/search");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 2,
+                "issues": [
+                    {"fields": {"summary": "no key"}},
+                    {"key": "KEY-1", "fields": {"subtasks": [{"id": "1"}]}}
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["KEY-1".to_string()],
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap();
+
+    // verify
+    assert_eq!(result.issues.len(), 1);
+    assert_eq!(result.issues[0].summary, "");
+    assert_eq!(
+        result.report.warnings,
+        vec![ChunkFailure {
+            keys: vec![],
+            error: ApiError::InvalidResponse("key of issue is not found".to_string()),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn do_not_report_keys_requested_in_lowercase() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .body_contains(r#"key in (\"KEY-1\")"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 1,
+                "issues": [
+                    {"key": "KEY-1", "fields": {"summary": ""}}
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueLoadingRequest {
+        issues: vec!["key-1".to_string()],
+        ..Default::default()
+    };
+    let result = load_issue(&request, url).await.unwrap();
+
+    // verify
+    mock.assert();
+    assert_eq!(result.issues[0].key, "KEY-1");
+    assert_eq!(result.report, LoadingReport::default());
+}
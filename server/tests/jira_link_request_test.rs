@@ -2,6 +2,9 @@ use std::collections::HashMap;
 
 use httpmock::{Method, MockServer};
 use jira_issue_loader::{
+    api_type::IssueLinkTypeRef,
+    error::ApiError,
+    issue::JiraIssueLinkType,
     jira_link_request::{create_link, delete_link},
     jira_url::JiraUrl,
 };
@@ -21,8 +24,8 @@ impl JiraUrl for TestRequest<'_> {
     }
 }
 
-#[test]
-fn request_to_create_link() {
+#[tokio::test]
+async fn request_to_create_link() {
     // arrange
     let server = httpmock::MockServer::start();
     let project_mock = server.mock(|when, then| {
@@ -52,15 +55,81 @@ fn request_to_create_link() {
 
     // do
     let url = TestRequest { server: &server };
-    let result = create_link("key1", "key2", &url).unwrap();
+    let result = create_link("key1", "key2", &IssueLinkTypeRef::default(), &url)
+        .await
+        .unwrap();
 
     // verify
     project_mock.assert();
     assert_eq!(result.id, "12345");
+    assert_eq!(result.link_type, None);
 }
 
-#[test]
-fn request_to_delete_link() {
+#[tokio::test]
+async fn request_to_create_link_with_link_type() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let project_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/issueLink")
+            .json_body_partial(
+                serde_json::json!({
+                    "type": {
+                        "id": "10001"
+                    }
+                })
+                .to_string(),
+            );
+
+        then.status(201).header(
+            "Location",
+            "https://foo.jira.com/rest/api/3/issueLink/12345",
+        );
+    });
+    server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/rest/api/3/issueLink/12345")
+            .header("authorization", "foo");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "id": "12345",
+                "type": {
+                    "id": "10001",
+                    "name": "Relates",
+                    "inward": "relates to",
+                    "outward": "relates to"
+                }
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let result = create_link(
+        "key1",
+        "key2",
+        &IssueLinkTypeRef::Id("10001".to_string()),
+        &url,
+    )
+    .await
+    .unwrap();
+
+    // verify
+    project_mock.assert();
+    assert_eq!(
+        result.link_type,
+        Some(JiraIssueLinkType {
+            id: "10001".to_string(),
+            name: "Relates".to_string(),
+            inward: "relates to".to_string(),
+            outward: "relates to".to_string(),
+        })
+    );
+}
+
+#[tokio::test]
+async fn request_to_delete_link() {
     // arrange
     let server = httpmock::MockServer::start();
     let project_mock = server.mock(|when, then| {
@@ -74,8 +143,27 @@ fn request_to_delete_link() {
 
     // do
     let url = TestRequest { server: &server };
-    delete_link("12345", &url).unwrap();
+    delete_link("12345", &url).await.unwrap();
 
     // verify
     project_mock.assert();
 }
+
+#[tokio::test]
+async fn return_error_when_issue_is_not_found() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::DELETE)
+            .path("/rest/api/3/issueLink/12345");
+
+        then.status(404);
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let result = delete_link("12345", &url).await;
+
+    // verify
+    assert_eq!(result, Err(ApiError::NotFound));
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use httpmock::{Method, MockServer};
+use jira_issue_loader::{
+    issue::JiraIssueLinkType, jira_link_type_request::get_link_types, jira_url::JiraUrl,
+};
+
+struct TestRequest<'a> {
+    server: &'a MockServer,
+}
+impl JiraUrl for TestRequest<'_> {
+    fn get_url(&self, path: &str) -> String {
+        self.server.url(path)
+    }
+
+    fn get_base_headers(&self) -> std::collections::HashMap<String, String> {
+        let mut map = HashMap::new();
+        map.insert("authorization".to_string(), "foo".to_string());
+        map
+    }
+}
+
+#[tokio::test]
+async fn request_to_get_link_types() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/rest/api/3/issueLinkType")
+            .header("authorization", "foo");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "issueLinkTypes": [
+                    {
+                        "id": "1000",
+                        "name": "Blocks",
+                        "inward": "is blocked by",
+                        "outward": "blocks",
+                        "self": "https://your-domain.atlassian.net/rest/api/3/issueLinkType/1000"
+                    },
+                    {
+                        "id": "1010",
+                        "name": "Duplicate",
+                        "inward": "duplicated by",
+                        "outward": "duplicates",
+                        "self": "https://your-domain.atlassian.net/rest/api/3/issueLinkType/1010"
+                    }
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let result = get_link_types(url).await.unwrap();
+
+    // verify
+    mock.assert();
+    assert_eq!(
+        result,
+        vec![
+            JiraIssueLinkType {
+                id: "1000".to_string(),
+                name: "Blocks".to_string(),
+                inward: "is blocked by".to_string(),
+                outward: "blocks".to_string(),
+            },
+            JiraIssueLinkType {
+                id: "1010".to_string(),
+                name: "Duplicate".to_string(),
+                inward: "duplicated by".to_string(),
+                outward: "duplicates".to_string(),
+            }
+        ]
+    );
+}
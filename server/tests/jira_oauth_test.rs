@@ -0,0 +1,200 @@
+use httpmock::{Method, MockServer};
+use jira_issue_loader::{
+    error::ApiError,
+    jira_oauth::{
+        refresh_token_cookie, refresh_token_from_cookie, JiraCloudResource, OAuthConfig, OAuthToken,
+    },
+};
+
+fn config(server: &MockServer) -> OAuthConfig {
+    OAuthConfig {
+        client_id: "client".to_string(),
+        client_secret: "secret".to_string(),
+        redirect_uri: "https://app.example.com/callback".to_string(),
+        authorize_url: url::Url::parse(&server.url("/authorize")).unwrap(),
+        token_url: server.url("/oauth/token"),
+        resources_url: server.url("/oauth/token/accessible-resources"),
+    }
+}
+
+#[tokio::test]
+async fn make_authorize_url() {
+    // arrange
+    let server = httpmock::MockServer::start();
+
+    // do
+    let url = config(&server).get_authorize_url("state-value");
+
+    // verify
+    assert!(url.starts_with(&server.url("/authorize?audience=api.atlassian.com")));
+    assert!(url.contains("client_id=client"));
+    assert!(url.contains("redirect_uri=https%3A%2F%2Fapp.example.com%2Fcallback"));
+    assert!(url.contains("state=state-value"));
+    assert!(url.contains("response_type=code"));
+}
+
+#[tokio::test]
+async fn exchange_code_with_token() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/oauth/token")
+            .json_body(serde_json::json!({
+                "grant_type": "authorization_code",
+                "client_id": "client",
+                "client_secret": "secret",
+                "code": "code",
+                "redirect_uri": "https://app.example.com/callback"
+            }));
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "access_token": "access",
+                "refresh_token": "refresh",
+                "expires_in": 3600,
+                "scope": "read:jira-work"
+            }));
+    });
+
+    // do
+    let token = config(&server).exchange_code("code").await;
+
+    // verify
+    mock.assert();
+    assert_eq!(
+        token,
+        Ok(OAuthToken {
+            access_token: "access".to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_in: 3600,
+        })
+    );
+}
+
+#[tokio::test]
+async fn refresh_token() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/oauth/token")
+            .json_body_partial(
+                serde_json::json!({
+                    "grant_type": "refresh_token",
+                    "refresh_token": "refresh"
+                })
+                .to_string(),
+            );
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "access_token": "new-access",
+                "refresh_token": "new-refresh",
+                "expires_in": 3600
+            }));
+    });
+
+    // do
+    let token = config(&server).refresh_token("refresh").await.unwrap();
+
+    // verify
+    mock.assert();
+    assert_eq!(token.access_token, "new-access");
+    assert_eq!(token.refresh_token, Some("new-refresh".to_string()));
+}
+
+#[tokio::test]
+async fn failed_to_exchange_code() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::POST).path("/oauth/token");
+
+        then.status(403)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "error": "access_denied"
+            }));
+    });
+
+    // do
+    let token = config(&server).exchange_code("code").await;
+
+    // verify
+    assert_eq!(token, Err(ApiError::Forbidden));
+}
+
+#[tokio::test]
+async fn get_accessible_resources() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/oauth/token/accessible-resources")
+            .header("authorization", "Bearer access");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!([
+                {
+                    "id": "cloud-id",
+                    "name": "site",
+                    "url": "https://site.atlassian.net",
+                    "scopes": ["read:jira-work"],
+                    "avatarUrl": "https://avatar"
+                }
+            ]));
+    });
+
+    // do
+    let resources = config(&server).get_accessible_resources("access").await;
+
+    // verify
+    mock.assert();
+    assert_eq!(
+        resources,
+        Ok(vec![JiraCloudResource {
+            id: "cloud-id".to_string(),
+            name: "site".to_string(),
+            url: "https://site.atlassian.net".to_string(),
+        }])
+    );
+}
+
+#[test]
+fn keep_refresh_token_out_of_json() {
+    // arrange
+    let token = OAuthToken {
+        access_token: "access".to_string(),
+        refresh_token: Some("refresh".to_string()),
+        expires_in: 3600,
+    };
+
+    // do
+    let json = serde_json::to_value(&token).unwrap();
+
+    // verify
+    assert_eq!(
+        json,
+        serde_json::json!({"accessToken": "access", "expiresIn": 3600})
+    );
+}
+
+#[test]
+fn keep_refresh_token_in_http_only_cookie() {
+    // arrange
+    let cookie = refresh_token_cookie("refresh");
+
+    // do
+    let token = refresh_token_from_cookie("other=value; jira_refresh_token=refresh");
+
+    // verify
+    assert!(cookie.starts_with("jira_refresh_token=refresh;"));
+    assert!(cookie.contains("HttpOnly"));
+    assert!(cookie.contains("Secure"));
+    assert_eq!(token, Some("refresh".to_string()));
+    assert_eq!(refresh_token_from_cookie("other=value"), None);
+}
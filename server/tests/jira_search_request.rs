@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
+use base64::{engine::general_purpose, Engine as _};
 use httpmock::{Method, MockServer};
 use jira_issue_loader::{
     api_type::IssueSearchRequest,
-    issue::{JiraIssueLink, JiraIssueType},
-    jira_search_request::search_issues,
-    jira_url::JiraUrl,
+    error::ApiError,
+    issue::{EstimateKind, JiraIssueEstimate, JiraIssueLink, JiraIssueLinkType, JiraIssueType},
+    jira_search_request::{approximate_count, search_issues},
+    jira_url::{JiraUrl, SearchApi},
 };
 
 struct TestRequest<'a> {
@@ -23,8 +25,26 @@ impl JiraUrl for TestRequest<'_> {
     }
 }
 
-#[test]
-fn request_to_get_an_issue() {
+/// request with `search/jql` API
+struct TestJqlRequest<'a> {
+    server: &'a MockServer,
+}
+impl JiraUrl for TestJqlRequest<'_> {
+    fn get_url(&self, path: &str) -> String {
+        self.server.url(path)
+    }
+
+    fn get_base_headers(&self) -> std::collections::HashMap<String, String> {
+        HashMap::new()
+    }
+
+    fn search_api(&self) -> SearchApi {
+        SearchApi::Jql
+    }
+}
+
+#[tokio::test]
+async fn request_to_get_an_issue() {
     // arrange
     let server = httpmock::MockServer::start();
     let _mock = server.mock(|when, then| {
@@ -51,9 +71,17 @@ fn request_to_get_an_issue() {
                                 "name": "type",
                                 "iconUrl": "url"
                             },
+                            "customfield_10016": 3.0,
+                            "timeoriginalestimate": 7200,
                             "issuelinks": [
                                 {
                                     "id": "100",
+                                    "type": {
+                                        "id": "10000",
+                                        "name": "Blocks",
+                                        "inward": "is blocked by",
+                                        "outward": "blocks"
+                                    },
                                     "outwardIssue": {
                                         "key": "other"
                                     }
@@ -70,8 +98,9 @@ fn request_to_get_an_issue() {
     let request = IssueSearchRequest {
         jql: "jql".to_string(),
         page: 1,
+        ..Default::default()
     };
-    let result = search_issues(&request, url).unwrap();
+    let result = search_issues(&request, url).await.unwrap().issues;
 
     // verify
     assert_eq!(result.len(), 1);
@@ -88,18 +117,32 @@ fn request_to_get_an_issue() {
             avatar_url: Some("url".to_string()),
         }
     );
+    assert_eq!(
+        result[0].estimate,
+        Some(JiraIssueEstimate {
+            story_points: Some(3.0),
+            original_estimate: Some(7200),
+        })
+    );
+    assert_eq!(result[0].estimate_of(EstimateKind::OriginalEstimate), 2.0);
     assert_eq!(
         result[0].links[0],
         JiraIssueLink {
             id: "100".to_string(),
             inward_issue: "test".to_string(),
-            outward_issue: "other".to_string()
+            outward_issue: "other".to_string(),
+            link_type: Some(JiraIssueLinkType {
+                id: "10000".to_string(),
+                name: "Blocks".to_string(),
+                inward: "is blocked by".to_string(),
+                outward: "blocks".to_string(),
+            }),
         }
     );
 }
 
-#[test]
-fn request_to_next_page() {
+#[tokio::test]
+async fn request_to_next_page() {
     // arrange
     let server = httpmock::MockServer::start();
     let mock = server.mock(|when, then| {
@@ -129,8 +172,9 @@ fn request_to_next_page() {
     let request = IssueSearchRequest {
         page: 2,
         jql: "jql".to_string(),
+        ..Default::default()
     };
-    let result = search_issues(&request, url).unwrap();
+    let result = search_issues(&request, url).await.unwrap().issues;
 
     // verify
     mock.assert();
@@ -140,5 +184,391 @@ fn request_to_next_page() {
     assert_eq!(result[0].description, None);
     assert_eq!(result[0].self_url, None);
     assert_eq!(result[0].status, None);
+    assert_eq!(result[0].estimate, None);
     assert_eq!(result[0].links.len(), 0);
 }
+
+#[tokio::test]
+async fn return_error_with_invalid_jql() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/search");
+
+        then.status(400)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "errorMessages": ["Error in the JQL Query"],
+                "errors": {}
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueSearchRequest {
+        page: 1,
+        jql: "jql".to_string(),
+        ..Default::default()
+    };
+    let result = search_issues(&request, url).await.map(|v| v.issues);
+
+    // verify
+    assert_eq!(
+        result.unwrap_err(),
+        ApiError::InvalidRequest("Error in the JQL Query".to_string())
+    );
+}
+
+#[tokio::test]
+async fn return_error_with_invalid_token() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/search");
+
+        then.status(401);
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueSearchRequest {
+        page: 1,
+        jql: "jql".to_string(),
+        ..Default::default()
+    };
+    let result = search_issues(&request, url).await.map(|v| v.issues);
+
+    // verify
+    assert_eq!(result.unwrap_err(), ApiError::Unauthorized);
+}
+
+#[tokio::test]
+async fn follow_page_tokens_to_next_page() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let first_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search/jql")
+            .json_body_partial(r#"{"fields": ["id"]}"#)
+            .matches(|req| {
+                !String::from_utf8_lossy(req.body.as_deref().unwrap_or_default())
+                    .contains("nextPageToken")
+            });
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "issues": [{"key": "first", "fields": {"summary": ""}}],
+                "nextPageToken": "token-2",
+                "isLast": false
+            }));
+    });
+    let second_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search/jql")
+            .json_body_partial(r#"{"nextPageToken": "token-2"}"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "issues": [{"key": "second", "fields": {"summary": ""}}],
+                "isLast": true
+            }));
+    });
+
+    let count_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search/approximate-count");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({"count": 2}));
+    });
+
+    // do
+    let url = TestJqlRequest { server: &server };
+    let request = IssueSearchRequest {
+        page: 2,
+        jql: "jql".to_string(),
+        ..Default::default()
+    };
+    let result = search_issues(&request, url).await.unwrap();
+
+    // verify
+    first_mock.assert();
+    second_mock.assert();
+    count_mock.assert();
+    assert_eq!(result.issues.len(), 1);
+    assert_eq!(result.issues[0].key, "second");
+    assert_eq!(result.total, Some(2));
+    assert!(!result.has_next);
+}
+
+#[tokio::test]
+async fn get_approximate_count() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search/approximate-count")
+            .json_body(serde_json::json!({"jql": "project = TEST"}));
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({"count": 120}));
+    });
+
+    // do
+    let url = TestJqlRequest { server: &server };
+    let result = approximate_count("project = TEST", &url).await;
+
+    // verify
+    mock.assert();
+    assert_eq!(result, Ok(120));
+}
+
+#[tokio::test]
+async fn return_pagination_with_cursor_of_next_page() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let first_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .json_body_partial(r#"{"startAt": 0, "maxResults": 2}"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 3,
+                "issues": [
+                    {"key": "KEY-1", "fields": {"summary": ""}},
+                    {"key": "KEY-2", "fields": {"summary": ""}}
+                ]
+            }));
+    });
+    let second_mock = server.mock(|when, then| {
+        when.method(Method::POST)
+            .path("/rest/api/3/search")
+            .json_body_partial(r#"{"startAt": 2, "maxResults": 2}"#);
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 3,
+                "issues": [
+                    {"key": "KEY-3", "fields": {"summary": ""}}
+                ]
+            }));
+    });
+
+    // do
+    let request = IssueSearchRequest {
+        jql: "jql".to_string(),
+        page: 0,
+        page_size: Some(2),
+        ..Default::default()
+    };
+    let first = search_issues(&request, TestRequest { server: &server })
+        .await
+        .unwrap();
+    let request = IssueSearchRequest {
+        jql: "jql".to_string(),
+        page_size: Some(2),
+        cursor: first.cursor.clone(),
+        ..Default::default()
+    };
+    let second = search_issues(&request, TestRequest { server: &server })
+        .await
+        .unwrap();
+
+    // verify
+    first_mock.assert();
+    second_mock.assert();
+    assert_eq!(first.page, 1);
+    assert_eq!(first.page_size, 2);
+    assert_eq!(first.total, Some(3));
+    assert!(first.has_next);
+    assert_eq!(second.page, 2);
+    assert_eq!(second.issues[0].key, "KEY-3");
+    assert!(!second.has_next);
+    assert_eq!(second.cursor, None);
+}
+
+#[tokio::test]
+async fn reject_invalid_cursor() {
+    // arrange
+    let server = httpmock::MockServer::start();
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueSearchRequest {
+        jql: "jql".to_string(),
+        cursor: Some("invalid".to_string()),
+        ..Default::default()
+    };
+    let result = search_issues(&request, url).await;
+
+    // verify
+    assert_eq!(
+        result.err(),
+        Some(ApiError::InvalidRequest("invalid cursor".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn reject_cursor_of_last_page_number() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/search");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 3,
+                "issues": [{"key": "KEY-1", "fields": {"summary": ""}}]
+            }));
+    });
+    let cursor = general_purpose::URL_SAFE_NO_PAD.encode(
+        serde_json::json!({
+            "jql": "jql",
+            "pageSize": 1,
+            "page": u32::MAX,
+            "position": {"offset": 0}
+        })
+        .to_string(),
+    );
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueSearchRequest {
+        jql: "jql".to_string(),
+        page_size: Some(1),
+        cursor: Some(cursor),
+        ..Default::default()
+    };
+    let result = search_issues(&request, url).await;
+
+    // verify
+    mock.assert();
+    assert_eq!(
+        result.err(),
+        Some(ApiError::InvalidRequest("invalid cursor".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn reject_cursor_of_another_search() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/search");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 3,
+                "issues": [{"key": "KEY-1", "fields": {"summary": ""}}]
+            }));
+    });
+    let request = IssueSearchRequest {
+        jql: "project = A".to_string(),
+        page_size: Some(1),
+        ..Default::default()
+    };
+    let first = search_issues(&request, TestRequest { server: &server })
+        .await
+        .unwrap();
+
+    // do
+    let other_jql = IssueSearchRequest {
+        jql: "project = B".to_string(),
+        page_size: Some(1),
+        cursor: first.cursor.clone(),
+        ..Default::default()
+    };
+    let other_page_size = IssueSearchRequest {
+        jql: "project = A".to_string(),
+        page_size: Some(2),
+        cursor: first.cursor.clone(),
+        ..Default::default()
+    };
+    let other_jql = search_issues(&other_jql, TestRequest { server: &server }).await;
+    let other_page_size = search_issues(&other_page_size, TestRequest { server: &server }).await;
+
+    // verify
+    mock.assert_hits(1);
+    assert_eq!(
+        other_jql.err(),
+        Some(ApiError::InvalidRequest(
+            "cursor does not match the search".to_string()
+        ))
+    );
+    assert_eq!(
+        other_page_size.err(),
+        Some(ApiError::InvalidRequest(
+            "cursor does not match the search".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn reject_far_page_without_cursor() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST);
+
+        then.status(500);
+    });
+
+    // do
+    let url = TestJqlRequest { server: &server };
+    let request = IssueSearchRequest {
+        page: 21,
+        jql: "jql".to_string(),
+        ..Default::default()
+    };
+    let result = search_issues(&request, url).await;
+
+    // verify
+    mock.assert_hits(0);
+    assert_eq!(
+        result.err(),
+        Some(ApiError::InvalidRequest(
+            "page after 20 must be requested with cursor".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn skip_malformed_issue() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::POST).path("/rest/api/3/search");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "total": 2,
+                "issues": [
+                    {"fields": {"summary": "no key"}},
+                    {"key": "KEY-2", "fields": {"summary": ""}}
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = IssueSearchRequest {
+        jql: "jql".to_string(),
+        ..Default::default()
+    };
+    let result = search_issues(&request, url).await.unwrap();
+
+    // verify
+    mock.assert();
+    assert_eq!(result.issues.len(), 1);
+    assert_eq!(result.issues[0].key, "KEY-2");
+}
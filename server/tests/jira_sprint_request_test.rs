@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use httpmock::{Method, MockServer};
+use jira_issue_loader::{
+    api_type::{BoardListRequest, SprintListRequest},
+    error::ApiError,
+    jira_sprint_request::{
+        get_boards, get_sprints, resolve_sprint_ids, JiraBoard, JiraSprint, SprintState,
+    },
+    jira_url::JiraUrl,
+};
+
+struct TestRequest<'a> {
+    server: &'a MockServer,
+}
+impl JiraUrl for TestRequest<'_> {
+    fn get_url(&self, path: &str) -> String {
+        self.server.url(path)
+    }
+
+    fn get_base_headers(&self) -> std::collections::HashMap<String, String> {
+        let mut map = HashMap::new();
+        map.insert("authorization".to_string(), "foo".to_string());
+        map
+    }
+}
+
+#[tokio::test]
+async fn use_number_as_id_of_sprint() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET);
+        then.status(500);
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let result = resolve_sprint_ids("42", &url).await;
+
+    // verify
+    assert_eq!(result, Ok(vec![42]));
+    mock.assert_hits(0);
+}
+
+#[tokio::test]
+async fn resolve_name_of_sprint_in_all_boards() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/rest/api/3/jql/autocompletedata/suggestions")
+            .query_param("fieldName", "sprint")
+            .query_param("fieldValue", "Sprint 4")
+            .header("authorization", "foo");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "results": [
+                    {"value": "4", "displayName": "<b>Sprint 4</b> (Board A)"},
+                    {"value": "40", "displayName": "<b>Sprint 4</b>0 (Board A)"},
+                    {"value": "7", "displayName": "<b>Sprint 4</b> (Board B)"}
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let result = resolve_sprint_ids("Sprint 4", &url).await;
+
+    // verify
+    assert_eq!(result, Ok(vec![4, 7]));
+    mock.assert();
+}
+
+#[tokio::test]
+async fn reject_unknown_sprint() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/rest/api/3/jql/autocompletedata/suggestions");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({ "results": [] }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let result = resolve_sprint_ids("Sprint 4", &url).await;
+
+    // verify
+    assert_eq!(
+        result,
+        Err(ApiError::InvalidRequest(
+            "unknown sprint: Sprint 4".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn get_boards_of_project() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/rest/agile/1.0/board")
+            .query_param("projectKeyOrId", "PROJ")
+            .query_param("startAt", "10")
+            .query_param("maxResults", "10")
+            .header("authorization", "foo");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "startAt": 10,
+                "maxResults": 10,
+                "isLast": false,
+                "values": [
+                    {
+                        "id": 1,
+                        "name": "PROJ board",
+                        "type": "scrum",
+                        "location": {"projectKey": "PROJ"}
+                    }
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = BoardListRequest {
+        project: Some("PROJ".to_string()),
+        page: 2,
+        page_size: Some(10),
+        ..Default::default()
+    };
+    let result = get_boards(&request, url).await.unwrap();
+
+    // verify
+    assert_eq!(
+        result.boards,
+        vec![JiraBoard {
+            id: 1,
+            name: "PROJ board".to_string(),
+            board_type: "scrum".to_string(),
+            project_key: Some("PROJ".to_string()),
+        }]
+    );
+    assert_eq!(result.page, 2);
+    assert!(result.has_next);
+    mock.assert();
+}
+
+#[tokio::test]
+async fn get_sprints_with_states() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/rest/agile/1.0/board/1/sprint")
+            .query_param("state", "active,future")
+            .query_param("startAt", "0")
+            .query_param("maxResults", "50");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "startAt": 0,
+                "maxResults": 50,
+                "isLast": true,
+                "values": [
+                    {
+                        "id": 42,
+                        "self": "https://self.url",
+                        "state": "active",
+                        "name": "Sprint 42",
+                        "startDate": "2024-01-01T00:00:00.000Z",
+                        "endDate": "2024-01-15T00:00:00.000Z",
+                        "originBoardId": 1,
+                        "goal": "goal"
+                    }
+                ]
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = SprintListRequest {
+        board_id: 1,
+        states: vec![SprintState::Active, SprintState::Future],
+        ..Default::default()
+    };
+    let result = get_sprints(&request, url).await.unwrap();
+
+    // verify
+    assert_eq!(
+        result.sprints,
+        vec![JiraSprint {
+            id: 42,
+            name: "Sprint 42".to_string(),
+            state: SprintState::Active,
+            start_date: Some("2024-01-01T00:00:00.000Z".to_string()),
+            end_date: Some("2024-01-15T00:00:00.000Z".to_string()),
+            complete_date: None,
+            origin_board_id: Some(1),
+            goal: Some("goal".to_string()),
+        }]
+    );
+    assert!(!result.has_next);
+    mock.assert();
+}
+
+#[tokio::test]
+async fn get_boards_of_large_page() {
+    // arrange
+    let server = httpmock::MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(Method::GET)
+            .path("/rest/agile/1.0/board")
+            .query_param("startAt", "214748364700")
+            .query_param("maxResults", "50");
+
+        then.status(200)
+            .header("content-type", "application/json")
+            .json_body(serde_json::json!({
+                "isLast": true,
+                "values": []
+            }));
+    });
+
+    // do
+    let url = TestRequest { server: &server };
+    let request = BoardListRequest {
+        page: u32::MAX,
+        ..Default::default()
+    };
+    let result = get_boards(&request, url).await.unwrap();
+
+    // verify
+    mock.assert();
+    assert_eq!(result.boards, vec![]);
+    assert_eq!(result.page, u32::MAX);
+}
@@ -21,3 +21,110 @@ async fn test_parse_request() {
     // verify
     let _response = handler(request).await.expect("failed to handle request");
 }
+
+#[tokio::test]
+async fn return_json_error_without_credential_headers() {
+    // arrange
+    let input = serde_json::json!({
+        "issues": ["key-1"]
+    });
+
+    // do
+    let request = lambda_http::http::Request::builder()
+        .method("POST")
+        .uri("http://localhost/prod/get-issues")
+        .body(input.to_string().into())
+        .expect("failed to create request");
+    let response = handler(request).await.expect("failed to handle request");
+
+    // verify
+    assert_eq!(response.status(), 400);
+    assert_eq!(
+        response.body(),
+        &lambda_http::Body::Text(
+            r#"{"code":"missing_header","message":"Missing header: x-user-domain"}"#.to_string()
+        )
+    );
+}
+
+#[tokio::test]
+async fn return_cors_headers_for_allowed_origin() {
+    // arrange
+    let preflight = lambda_http::http::Request::builder()
+        .method("OPTIONS")
+        .uri("http://localhost/prod/get-issues")
+        .header("origin", "http://localhost:5173")
+        .body(lambda_http::Body::Empty)
+        .expect("failed to create request");
+    let error = lambda_http::http::Request::builder()
+        .method("POST")
+        .uri("http://localhost/prod/get-issues")
+        .header("origin", "http://localhost:5173")
+        .body("{}".into())
+        .expect("failed to create request");
+
+    // do
+    let preflight = handler(preflight).await.expect("failed to handle request");
+    let error = handler(error).await.expect("failed to handle request");
+
+    // verify
+    assert_eq!(
+        preflight.headers()["access-control-allow-origin"],
+        "http://localhost:5173"
+    );
+    assert!(preflight
+        .headers()
+        .contains_key("access-control-allow-headers"));
+    assert_eq!(
+        error.headers()["access-control-allow-origin"],
+        "http://localhost:5173"
+    );
+    assert_eq!(error.headers()["access-control-allow-credentials"], "true");
+}
+
+#[tokio::test]
+async fn do_not_allow_unknown_origin() {
+    // arrange
+    let request = lambda_http::http::Request::builder()
+        .method("OPTIONS")
+        .uri("http://localhost/prod/get-issues")
+        .header("origin", "https://evil.example.com")
+        .body(lambda_http::Body::Empty)
+        .expect("failed to create request");
+
+    // do
+    let response = handler(request).await.expect("failed to handle request");
+
+    // verify
+    assert!(!response
+        .headers()
+        .contains_key("access-control-allow-origin"));
+}
+
+#[tokio::test]
+async fn reject_base_url_not_in_allow_list() {
+    // arrange
+    let input = serde_json::json!({
+        "issues": ["KEY-1"]
+    });
+
+    // do
+    let request = lambda_http::http::Request::builder()
+        .method("POST")
+        .uri("http://localhost/prod/get-issues")
+        .header("x-user-base-url", "http://169.254.169.254/latest")
+        .header("x-user-token", "token")
+        .body(input.to_string().into())
+        .expect("failed to create request");
+    let response = handler(request).await.expect("failed to handle request");
+
+    // verify
+    assert_eq!(response.status(), 400);
+    assert_eq!(
+        response.body(),
+        &lambda_http::Body::Text(
+            r#"{"code":"invalid_request","message":"Invalid request: base URL is not allowed: http://169.254.169.254/latest"}"#
+                .to_string()
+        )
+    );
+}
@@ -0,0 +1,102 @@
+use jira_issue_loader::router::Router;
+use lambda_http::{http::Method, request::RequestContext, Body, Request, Response};
+
+fn router(base_path: Option<&str>) -> Router {
+    Router::with_base_path(base_path.map(|v| v.to_string()), |_| {
+        Box::pin(async { Ok(Response::new(Body::Text("preflight".to_string()))) })
+    })
+    .post("/issues", |_| {
+        Box::pin(async { Ok(Response::new(Body::Text("issues".to_string()))) })
+    })
+    .get("/authorize", |_| {
+        Box::pin(async { Ok(Response::new(Body::Text("authorize".to_string()))) })
+    })
+}
+
+fn request(method: Method, uri: &str) -> Request {
+    lambda_http::http::Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::Empty)
+        .expect("failed to create request")
+}
+
+#[tokio::test]
+async fn dispatch_to_route_under_default_base_path() {
+    // do
+    let response = router(None)
+        .dispatch(&request(Method::POST, "http://localhost/prod/issues"))
+        .await
+        .unwrap();
+    let preflight = router(None)
+        .dispatch(&request(Method::OPTIONS, "http://localhost/prod/issues"))
+        .await
+        .unwrap();
+
+    // verify
+    assert_eq!(response.body(), &Body::Text("issues".to_string()));
+    assert_eq!(preflight.body(), &Body::Text("preflight".to_string()));
+}
+
+#[tokio::test]
+async fn dispatch_with_configured_base_path() {
+    // do
+    let response = router(Some("/staging/"))
+        .dispatch(&request(Method::POST, "http://localhost/staging/issues"))
+        .await
+        .unwrap();
+    let root = router(Some(""))
+        .dispatch(&request(Method::GET, "http://localhost/authorize"))
+        .await
+        .unwrap();
+
+    // verify
+    assert_eq!(response.body(), &Body::Text("issues".to_string()));
+    assert_eq!(root.body(), &Body::Text("authorize".to_string()));
+}
+
+#[tokio::test]
+async fn dispatch_with_stage_of_api_gateway() {
+    // arrange
+    let context: RequestContext = serde_json::from_value(serde_json::json!({
+        "stage": "staging",
+        "httpMethod": "POST",
+        "requestTimeEpoch": 0,
+        "identity": {}
+    }))
+    .expect("failed to parse context");
+    let mut event = request(Method::POST, "http://localhost/staging/issues");
+    event.extensions_mut().insert(context);
+
+    // do
+    let response = router(None).dispatch(&event).await.unwrap();
+    let not_found = router(None)
+        .dispatch(&request(Method::POST, "http://localhost/prod/other"))
+        .await
+        .unwrap_err()
+        .to_response();
+
+    // verify
+    assert_eq!(response.body(), &Body::Text("issues".to_string()));
+    assert_eq!(not_found.status(), 404);
+    assert_eq!(
+        not_found.body(),
+        &Body::Text(
+            r#"{"code":"route_not_found","message":"Route not found: /prod/other"}"#.to_string()
+        )
+    );
+}
+
+#[tokio::test]
+async fn return_allowed_methods_for_unmatched_method() {
+    // do
+    let error = router(None)
+        .dispatch(&request(Method::GET, "http://localhost/prod/issues"))
+        .await
+        .unwrap_err();
+    let response = error.to_response();
+
+    // verify
+    assert_eq!(response.status(), 405);
+    assert_eq!(response.headers().get("allow").unwrap(), "POST,OPTIONS");
+}
@@ -26,5 +26,5 @@ export const call = async function call(apiCredential: ApiCredential, targetIssu
 
   const json = await ret.json();
 
-  return mapResponse(json);
+  return mapResponse(json.issues);
 };
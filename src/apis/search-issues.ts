@@ -36,5 +36,5 @@ export const call = async function call(
     throw ret.statusText;
   }
 
-  return [mapResponse(json), undefined];
+  return [mapResponse(json.issues), undefined];
 };
@@ -94,7 +94,7 @@ export const DisplayIssues: Story = {
     msw: {
       handlers: [
         rest.post(`${MOCK_BASE_URL}/search-issues`, (_, res, ctx) => {
-          return res(ctx.delay(300), ctx.json([randomApiIssue(), randomApiIssue(), randomApiIssue()]));
+          return res(ctx.delay(300), ctx.json({ issues: [randomApiIssue(), randomApiIssue(), randomApiIssue()] }));
         }),
       ],
     },
@@ -78,7 +78,7 @@ test("change loading state of input query and search", async () => {
 
   server.use({
     searchIssues(_, res, ctx) {
-      return res(ctx.delay(1000), ctx.json([]));
+      return res(ctx.delay(1000), ctx.json({ issues: [] }));
     },
   });
 
@@ -103,7 +103,7 @@ test("display empty list", async () => {
 
   server.use({
     searchIssues(_, res, ctx) {
-      return res(ctx.json([]));
+      return res(ctx.json({ issues: [] }));
     },
   });
 
@@ -151,7 +151,7 @@ test("display issues when API returns some issues", async () => {
 
   server.use({
     searchIssues(_, res, ctx) {
-      return res(ctx.json([randomApiIssue({ key: "key" })]));
+      return res(ctx.json({ issues: [randomApiIssue({ key: "key" })] }));
     },
   });
 
@@ -175,7 +175,7 @@ test("disable backward pagination after initial search", async () => {
 
   server.use({
     searchIssues(_, res, ctx) {
-      return res(ctx.json([randomApiIssue({ key: "key" })]));
+      return res(ctx.json({ issues: [randomApiIssue({ key: "key" })] }));
     },
   });
 
@@ -199,7 +199,7 @@ test("change page after search", async () => {
 
   server.use({
     async searchIssues(_, res, ctx) {
-      return res(ctx.json([randomApiIssue({ key: "key" })]));
+      return res(ctx.json({ issues: [randomApiIssue({ key: "key" })] }));
     },
   });
 
@@ -212,7 +212,7 @@ test("change page after search", async () => {
 
       expect(json.page).toBe(2);
 
-      return res(ctx.json([randomApiIssue({ key: "key2" })]));
+      return res(ctx.json({ issues: [randomApiIssue({ key: "key2" })] }));
     },
   });
 
@@ -237,7 +237,7 @@ test("select issue to mark to import after", async () => {
 
   server.use({
     async searchIssues(_, res, ctx) {
-      return res(ctx.json([randomApiIssue({ key: "key" })]));
+      return res(ctx.json({ issues: [randomApiIssue({ key: "key" })] }));
     },
   });
 
@@ -268,13 +268,13 @@ test("execute import when some issues are selected", async () => {
 
   server.use({
     async searchIssues(_, res, ctx) {
-      return res(ctx.json([randomApiIssue({ key: "key" })]));
+      return res(ctx.json({ issues: [randomApiIssue({ key: "key" })] }));
     },
     async getIssues(req, res, ctx) {
       const json = await req.json();
       expect(json.issues).toContain("key");
 
-      return res(ctx.json([randomApiIssue({ key: "key" })]));
+      return res(ctx.json({ issues: [randomApiIssue({ key: "key" })] }));
     },
   });
 